@@ -0,0 +1,106 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, SaltString},
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use leptos::prelude::*;
+use leptos_axum::ResponseOptions;
+use rand::RngCore;
+use crate::models::user::AdminUser;
+use crate::state::AppState;
+
+pub const SESSION_COOKIE: &str = "blog_session";
+const SESSION_TTL_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .and_then(|hash| Argon2::default().verify_password(password.as_bytes(), &hash))
+        .is_ok()
+}
+
+/// Checks the credentials and, when they match, starts a session and sets
+/// its cookie on the current response.
+pub async fn login(username: &str, password: &str) -> Result<Option<AdminUser>, ServerFnError> {
+    let state = expect_context::<AppState>();
+
+    let user = match state.auth.get_admin_credentials(username).await? {
+        Some((user, password_hash)) if verify_password(password, &password_hash) => user,
+        _ => return Ok(None),
+    };
+
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let token = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+
+    state.auth.create_session(&token, user.id, SESSION_TTL_SECONDS).await?;
+    set_session_cookie(Some(&token));
+
+    Ok(Some(user))
+}
+
+/// Ends the request's session, if any, and clears its cookie.
+pub async fn logout() -> Result<(), ServerFnError> {
+    let state = expect_context::<AppState>();
+    let headers = leptos_axum::extract::<HeaderMap>().await?;
+
+    if let Some(token) = session_token(&headers) {
+        state.auth.delete_session(&token).await?;
+    }
+    set_session_cookie(None);
+
+    Ok(())
+}
+
+/// Returns the admin behind the request's session cookie, if any.
+pub async fn current_admin() -> Result<Option<AdminUser>, ServerFnError> {
+    let state = expect_context::<AppState>();
+    let headers = leptos_axum::extract::<HeaderMap>().await?;
+
+    match session_token(&headers) {
+        Some(token) => Ok(state.auth.find_session(&token).await?),
+        None => Ok(None),
+    }
+}
+
+/// Guard for admin server functions: rejects the call with `401 Unauthorized`
+/// unless the request carries a valid session.
+pub async fn require_admin() -> Result<AdminUser, ServerFnError> {
+    match current_admin().await? {
+        Some(user) => Ok(user),
+        None => {
+            if let Some(response) = use_context::<ResponseOptions>() {
+                response.set_status(StatusCode::UNAUTHORIZED);
+            }
+            Err(ServerFnError::ServerError("unauthorized".to_string()))
+        }
+    }
+}
+
+fn session_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token.to_string())
+}
+
+fn set_session_cookie(token: Option<&str>) {
+    let cookie = match token {
+        Some(token) => format!(
+            "{SESSION_COOKIE}={token}; Path=/; Max-Age={SESSION_TTL_SECONDS}; HttpOnly; Secure; SameSite=Lax"
+        ),
+        None => format!("{SESSION_COOKIE}=; Path=/; Max-Age=0; HttpOnly; Secure; SameSite=Lax"),
+    };
+
+    if let (Some(response), Ok(cookie)) = (use_context::<ResponseOptions>(), HeaderValue::from_str(&cookie)) {
+        response.append_header(header::SET_COOKIE, cookie);
+    }
+}
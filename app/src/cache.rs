@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::models::{
+    cache::CacheStats,
+    category::{CategoryCount, CategoryFilter},
+    post::{BlogPost, PostPage, PostSort},
+};
+
+/// Entries a single cache holds before expired ones are dropped, or all of
+/// them when none has expired yet.
+const MAX_ENTRIES: usize = 1024;
+
+/// Values kept for `ttl` after they were loaded, counting hits and misses.
+/// A zero `ttl` disables the cache: every lookup misses.
+#[derive(Debug)]
+pub struct TtlCache<K, V> {
+    name: &'static str,
+    ttl: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(name: &'static str, ttl: Duration) -> Self {
+        Self {
+            name,
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let value = self.entries()
+            .get(key)
+            .filter(|(loaded_at, _)| loaded_at.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone());
+
+        let counter = if value.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        let mut entries = self.entries();
+        if entries.len() >= MAX_ENTRIES {
+            entries.retain(|_, (loaded_at, _)| loaded_at.elapsed() < self.ttl);
+            if entries.len() >= MAX_ENTRIES {
+                entries.clear();
+            }
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// The cached value for `key`, or the one `load` returns, which is
+    /// cached unless it is an error.
+    pub async fn get_or_try_insert_with<E, F, Fut>(&self, key: K, load: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+
+        let value = load().await?;
+        self.insert(key, value.clone());
+        Ok(value)
+    }
+
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            name: self.name.to_string(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries().len(),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<K, (Instant, V)>> {
+        // A panic while holding the lock cannot leave the map half-updated.
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Arguments of `get_blog_posts`, with the cursor in its encoded form.
+type PostPageKey = (CategoryFilter, PostSort, Option<String>);
+
+/// Recent results of the repository reads behind the public pages, keyed
+/// by the arguments of the server function making them.
+#[derive(Debug, Clone)]
+pub struct ReadCache {
+    pub post_pages: Arc<TtlCache<PostPageKey, PostPage>>,
+    pub categories: Arc<TtlCache<(), Vec<CategoryCount>>>,
+    pub posts: Arc<TtlCache<String, BlogPost>>,
+    ttl: Duration,
+    /// Tells this server's cache apart from those of earlier runs.
+    instance: u128,
+    created_at: Instant,
+    /// Bumped by every `invalidate`.
+    generation: Arc<AtomicU64>,
+}
+
+impl ReadCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            post_pages: Arc::new(TtlCache::new("post pages", ttl)),
+            categories: Arc::new(TtlCache::new("categories", ttl)),
+            posts: Arc::new(TtlCache::new("posts", ttl)),
+            ttl,
+            instance: SystemTime::UNIX_EPOCH.elapsed().map_or(0, |since| since.as_nanos()),
+            created_at: Instant::now(),
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Changes whenever the cached reads may have: on every write, every
+    /// `ttl`, and when the server restarts. `None` while caching is
+    /// disabled, since reads may then change at any time.
+    pub fn version(&self) -> Option<String> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+
+        let period = self.created_at.elapsed().as_nanos() / self.ttl.as_nanos();
+        Some(format!("{:x}-{:x}-{period:x}", self.instance, self.generation.load(Ordering::Relaxed)))
+    }
+
+    /// Drops every cached read. Any write to a post can change listings,
+    /// category counts and the related posts of other posts, so writes
+    /// clear everything.
+    pub fn invalidate(&self) {
+        self.post_pages.clear();
+        self.categories.clear();
+        self.posts.clear();
+        // Only after clearing, so a new version never pairs with old reads.
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> Vec<CacheStats> {
+        vec![self.post_pages.stats(), self.categories.stats(), self.posts.stats()]
+    }
+}
@@ -3,3 +3,7 @@ pub mod bottom_nav;
 pub mod post;
 pub mod post_category;
 pub mod flappy_bird;
+pub mod post_editor;
+pub mod search_palette;
+pub mod post_tag;
+pub mod series_box;
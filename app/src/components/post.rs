@@ -1,5 +1,10 @@
 use leptos::prelude::*;
+use leptos_meta::{Link, Meta, Script, Title};
 use crate::models::post::BlogPost;
+use crate::components::post_card::PostCard;
+use crate::components::post_tag::PostTags;
+use crate::components::series_box::SeriesBox;
+use crate::{SITE_AUTHOR, SITE_TITLE};
 
 #[component]
 pub fn Post(post: BlogPost) -> impl IntoView {
@@ -7,33 +12,138 @@ pub fn Post(post: BlogPost) -> impl IntoView {
         <article class="max-w-4xl mx-auto py-12 px-4 sm:px-6 lg:px-8">
             <h1 class="text-4xl font-bold mb-4">{post.title.clone()}</h1>
             <div class="text-gray-500 mb-8">
-                {post.published_at}
+                {post.published_at} " · " {format!("{} min read", post.reading_time_minutes)}
+                " · " {format!("{} words", post.word_count)}
             </div>
+            {(!post.tags.is_empty()).then(|| view! {
+                <div class="mb-8">
+                    <PostTags tags=post.tags/>
+                </div>
+            })}
             <p class="text-xl text-gray-600 mb-8">
                 {post.description}
             </p>
-            <div class="mb-12">
-                <img
-                    src={post.hero_image}
-                    alt={format!("{} cover", post.title)}
-                    class="w-full h-96 object-cover rounded-lg shadow-lg"
-                />
-            </div>
+            {(!post.hero_image.is_empty()).then(|| view! {
+                <div class="mb-12">
+                    <img
+                        src={post.hero_image}
+                        alt={format!("{} cover", post.title)}
+                        class="w-full h-96 object-cover rounded-lg shadow-lg"
+                    />
+                </div>
+            })}
+            {post.series.map(|series| view! { <SeriesBox series post_id=post.id/> })}
             // <div
             //     class="prose prose-lg max-w-none"
             //     inner_html={post.content}
             // />
-            <section class="p-4 mt-4 table-of-contents-parent">
-                <h2 class="text-xl md:text-2xl">"Contents"</h2>
-                <div
-                    class="prose lg:prose-xl dark:prose-invert text-base md: w-full"
-                    inner_html={post.toc}
-                ></div>
-            </section>
-            <section
-                class="mx-auto prose lg:prose-xl dark:prose-invert text-base mt-8"
-                inner_html={post.content}
-            ></section>
+            <PostBody content=post.content toc=post.toc/>
         </article>
+        {(post.previous.is_some() || post.next.is_some()).then(|| view! {
+            <section class="max-w-4xl mx-auto px-4 sm:px-6 lg:px-8 mb-12">
+                <div class="grid grid-cols-1 md:grid-cols-2 gap-8">
+                    {post.previous.map(|previous| view! {
+                        <div>
+                            <h2 class="text-xl font-bold mb-4">"Previous post"</h2>
+                            <PostCard post=previous/>
+                        </div>
+                    })}
+                    {post.next.map(|next| view! {
+                        <div class="md:col-start-2">
+                            <h2 class="text-xl font-bold mb-4">"Next post"</h2>
+                            <PostCard post=next/>
+                        </div>
+                    })}
+                </div>
+            </section>
+        })}
+        {(!post.related.is_empty()).then(|| view! {
+            <section class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 mb-12">
+                <h2 class="text-2xl font-bold mb-4">"Related posts"</h2>
+                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
+                    {post.related.into_iter().map(|post| view! { <PostCard post=post/> }).collect::<Vec<_>>()}
+                </div>
+            </section>
+        })}
+    }
+}
+
+/// Table of contents and rendered content of a post.
+#[component]
+pub fn PostBody(content: String, toc: Option<String>) -> impl IntoView {
+    view! {
+        <section class="p-4 mt-4 table-of-contents-parent">
+            <h2 class="text-xl md:text-2xl">"Contents"</h2>
+            <div
+                class="prose lg:prose-xl dark:prose-invert text-base md: w-full"
+                inner_html={toc}
+            ></div>
+        </section>
+        <section
+            class="mx-auto prose lg:prose-xl dark:prose-invert text-base mt-8"
+            inner_html={content}
+        ></section>
+    }
+}
+
+/// Search engine and social sharing metadata for a post: title,
+/// description, canonical link, Open Graph and Twitter card tags, and
+/// JSON-LD `BlogPosting` structured data. Links are made absolute with the
+/// post's `site_url`. Posts without a hero image are shared with their
+/// generated `/og/{slug}.png` card.
+#[component]
+pub fn PostMeta(post: BlogPost) -> impl IntoView {
+    let site_url = &post.site_url;
+    let url = format!("{site_url}/blog/{}", post.slug);
+    let image = if post.hero_image.is_empty() {
+        format!("{site_url}/og/{}.png", post.slug)
+    } else if post.hero_image.starts_with('/') {
+        format!("{site_url}{}", post.hero_image)
+    } else {
+        post.hero_image.clone()
+    };
+    let tags = post.categories.iter().map(|category| category.name.clone())
+        .chain(post.tags.iter().map(|tag| tag.name.clone()))
+        .collect::<Vec<_>>();
+
+    let structured_data = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": post.title,
+        "description": post.description,
+        "image": image,
+        "url": url,
+        "mainEntityOfPage": url,
+        "datePublished": post.published_at_rfc3339,
+        "dateModified": post.updated_at_rfc3339,
+        "keywords": tags,
+        "author": { "@type": "Person", "name": SITE_AUTHOR },
+        "publisher": { "@type": "Person", "name": SITE_AUTHOR },
+    })
+    .to_string()
+    // Keep the JSON from closing the surrounding script element.
+    .replace("</", "<\\/");
+
+    view! {
+        <Title text=format!("{} | {SITE_TITLE}", post.title)/>
+        <Meta name="description" content=post.description.clone()/>
+        <Link rel="canonical" href=url.clone()/>
+
+        <Meta property="og:type" content="article"/>
+        <Meta property="og:site_name" content=SITE_TITLE/>
+        <Meta property="og:title" content=post.title.clone()/>
+        <Meta property="og:description" content=post.description.clone()/>
+        <Meta property="og:url" content=url/>
+        <Meta property="og:image" content=image.clone()/>
+        <Meta property="article:published_time" content=post.published_at_rfc3339/>
+        <Meta property="article:modified_time" content=post.updated_at_rfc3339/>
+        {tags.into_iter().map(|tag| view! { <Meta property="article:tag" content=tag/> }).collect::<Vec<_>>()}
+
+        <Meta name="twitter:card" content="summary_large_image"/>
+        <Meta name="twitter:title" content=post.title/>
+        <Meta name="twitter:description" content=post.description/>
+        <Meta name="twitter:image" content=image/>
+
+        <Script type_="application/ld+json">{structured_data}</Script>
     }
 }
@@ -1,8 +1,9 @@
 use leptos::prelude::*;
-use crate::models::post::BlogPost;
+use crate::models::post::PostSummary;
+use crate::components::post_tag::PostTags;
 
 #[component]
-pub fn PostCard(post: BlogPost) -> impl IntoView {
+pub fn PostCard(post: PostSummary) -> impl IntoView {
     view! {
         <div class="card shadow-lg overflow-hidden hover:shadow-xl transition-shadow duration-300">
             <div class="aspect-w-16 aspect-h-9">
@@ -21,13 +22,17 @@ pub fn PostCard(post: BlogPost) -> impl IntoView {
                 </h3>
 
                 <div class="text-secondary text-sm mb-3">
-                    {post.published_at}
+                    {post.published_at} " · " {format!("{} min read", post.reading_time_minutes)}
                 </div>
 
                 <p class="text-primary-content line-clamp-3">
                     {post.description}
                 </p>
 
+                {(!post.tags.is_empty()).then(|| view! {
+                    <PostTags tags=post.tags/>
+                })}
+
                 <div class="card-actions justify-end">
 
                     <a href={format!("/blog/{}", post.slug)} >
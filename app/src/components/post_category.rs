@@ -1,41 +1,54 @@
 use leptos::prelude::*;
 use leptos_router::components::*;
 
-use crate::models::category::Category;
+use crate::models::{category::{CategoryCount, CategoryFilter, CategoryMatch}, post::PostSort};
 
+const CHIP_CLASS: &str = "text-bold px-4 py-2 rounded-full transition-colors hover:bg-accent";
+const SELECTED_CHIP_CLASS: &str = "text-bold px-4 py-2 rounded-full transition-colors bg-accent";
+
+/// Category chips with their post counts; each one adds its category to the
+/// filter in the URL, or removes it when already selected, keeping the
+/// `sort` order. Categories without posts are left out.
 #[component]
 pub fn PostCategory(
-    categories: Vec<Category>,
-    selected_category: ReadSignal<Option<String>>,
+    categories: Vec<CategoryCount>,
+    #[prop(into)] filter: Signal<CategoryFilter>,
+    #[prop(into)] sort: Signal<PostSort>,
 ) -> impl IntoView {
     view! {
-        <div class="flex flex-wrap gap-2 mb-8">
-            <A href="/blog">
-                <div
-                    class={move || match selected_category.get() {
-                        None => "text-bold px-4 py-2 rounded-full transition-colors bg-accent",
-                        Some(_) => "text-bold px-4 py-2 rounded-full transition-colors hover:bg-accent",
-                    }}
-                >
+        <div class="flex flex-wrap items-center gap-2 mb-8">
+            <A href=move || CategoryFilter::default().href(sort.get())>
+                <div class=move || if filter.with(CategoryFilter::is_empty) { SELECTED_CHIP_CLASS } else { CHIP_CLASS }>
                     "All"
                 </div>
             </A>
-            {categories.into_iter().map(|cat| {
+            {categories.into_iter().filter(|count| count.post_count > 0).map(|CategoryCount { category: cat, post_count }| {
                 let cat_slug = cat.slug.clone();
+                let is_selected = move || filter.with(|filter| filter.contains(&cat_slug));
                 view! {
-                    <A href={format!("/blog?category={}", cat.slug)}>
-                        <div
-                        class={move || match selected_category.get() {
-                                Some(current_cat) if current_cat == cat_slug =>
-                                    "text-bold px-4 py-2 rounded-full transition-colors bg-accent",
-                                _ => "text-bold px-4 py-2 rounded-full transition-colors hover:bg-accent",
-                            }}
-                        >
-                            {cat.name}
+                    <A href=move || filter.with(|filter| filter.toggle(&cat.slug).href(sort.get()))>
+                        <div class=move || if is_selected() { SELECTED_CHIP_CLASS } else { CHIP_CLASS }>
+                            {cat.name.clone()}
+                            <span class="ml-2 badge badge-sm">{post_count}</span>
                         </div>
                     </A>
                 }
             }).collect::<Vec<_>>()}
+            <Show when=move || filter.with(|filter| filter.slugs.len() > 1)>
+                <div class="join ml-2">
+                    {[(CategoryMatch::Any, "Any"), (CategoryMatch::All, "All of them")].map(|(mode, label)| view! {
+                        <A href=move || filter.with(|filter| filter.with_mode(mode).href(sort.get()))>
+                            <div class=move || if filter.with(|filter| filter.mode == mode) {
+                                "btn btn-sm join-item btn-accent"
+                            } else {
+                                "btn btn-sm join-item"
+                            }>
+                                {label}
+                            </div>
+                        </A>
+                    })}
+                </div>
+            </Show>
         </div>
     }
 }
@@ -0,0 +1,224 @@
+use leptos::prelude::*;
+use leptos::either::*;
+use leptos::ev::SubmitEvent;
+use leptos_use::signal_debounced;
+
+use crate::models::{category::Category, post::{PostForm, PostStatus}, tag::parse_tag_names};
+use crate::components::post::PostBody;
+use crate::routes::admin::{preview_markdown, SavePost};
+
+const PREVIEW_DEBOUNCE_MS: f64 = 500.0;
+
+#[component]
+pub fn PostEditor(post: PostForm, categories: Vec<Category>) -> impl IntoView {
+    let save = ServerAction::<SavePost>::new();
+
+    let id = post.id;
+    let preview_href = post.preview_token.map(|token| format!("/blog/{}?preview={token}", post.slug));
+    let (title, set_title) = signal(post.title);
+    let (slug, set_slug) = signal(post.slug);
+    let (description, set_description) = signal(post.description);
+    let (hero_image, set_hero_image) = signal(post.hero_image);
+    let (content, set_content) = signal(post.content);
+    let (published_at, set_published_at) = signal(post.published_at);
+    let (status, set_status) = signal(post.status);
+    let (category_ids, set_category_ids) = signal(post.category_ids);
+    let (tags, set_tags) = signal(post.tags.join(", "));
+    let (series, set_series) = signal(post.series);
+    let (series_position, set_series_position) = signal(post.series_position.map(|position| position.to_string()).unwrap_or_default());
+
+    let debounced_content = signal_debounced(content, PREVIEW_DEBOUNCE_MS);
+    let preview = Resource::new(move || debounced_content.get(), preview_markdown);
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        save.dispatch(SavePost {
+            post: PostForm {
+                id,
+                title: title.get(),
+                slug: slug.get(),
+                description: description.get(),
+                hero_image: hero_image.get(),
+                content: content.get(),
+                published_at: published_at.get(),
+                status: status.get(),
+                category_ids: category_ids.get(),
+                tags: parse_tag_names(&tags.get()),
+                series: series.get(),
+                series_position: series_position.with(|position| position.trim().parse().ok()),
+                preview_token: None,
+            },
+        });
+    };
+
+    view! {
+        <form class="flex flex-col gap-4" on:submit=on_submit>
+            <label class="form-control">
+                <span class="label-text mb-1">"Title"</span>
+                <input
+                    type="text"
+                    class="input input-bordered"
+                    required
+                    prop:value=title
+                    on:input=move |ev| set_title(event_target_value(&ev))
+                />
+            </label>
+            <label class="form-control">
+                <span class="label-text mb-1">"Slug"</span>
+                <input
+                    type="text"
+                    class="input input-bordered"
+                    required
+                    pattern="[a-z0-9-]+"
+                    prop:value=slug
+                    on:input=move |ev| set_slug(event_target_value(&ev))
+                />
+            </label>
+            <label class="form-control">
+                <span class="label-text mb-1">"Description"</span>
+                <textarea
+                    class="textarea textarea-bordered"
+                    rows="2"
+                    prop:value=description
+                    on:input=move |ev| set_description(event_target_value(&ev))
+                ></textarea>
+            </label>
+            <label class="form-control">
+                <span class="label-text mb-1">"Hero image"</span>
+                <input
+                    type="text"
+                    class="input input-bordered"
+                    prop:value=hero_image
+                    on:input=move |ev| set_hero_image(event_target_value(&ev))
+                />
+            </label>
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <label class="form-control">
+                    <span class="label-text mb-1">"Publish date"</span>
+                    <input
+                        type="datetime-local"
+                        class="input input-bordered"
+                        required
+                        prop:value=published_at
+                        on:input=move |ev| set_published_at(event_target_value(&ev))
+                    />
+                </label>
+                <label class="form-control">
+                    <span class="label-text mb-1">"Status"</span>
+                    <select
+                        class="select select-bordered"
+                        on:change=move |ev| {
+                            if let Some(new_status) = PostStatus::parse(&event_target_value(&ev)) {
+                                set_status(new_status);
+                            }
+                        }
+                    >
+                        {PostStatus::ALL.into_iter().map(|option| view! {
+                            <option
+                                value=option.as_str()
+                                selected=move || status.get() == option
+                            >
+                                {option.as_str()}
+                            </option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </label>
+            </div>
+            <div class="flex flex-wrap gap-4">
+                {categories.into_iter().map(|cat| {
+                    let cat_id = cat.id;
+                    view! {
+                        <label class="label cursor-pointer gap-2">
+                            <input
+                                type="checkbox"
+                                class="checkbox checkbox-accent"
+                                prop:checked=move || category_ids.with(|ids| ids.contains(&cat_id))
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    set_category_ids.update(|ids| {
+                                        ids.retain(|id| *id != cat_id);
+                                        if checked {
+                                            ids.push(cat_id);
+                                        }
+                                    });
+                                }
+                            />
+                            <span class="label-text">{cat.name}</span>
+                        </label>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+            <label class="form-control">
+                <span class="label-text mb-1">"Tags"</span>
+                <input
+                    type="text"
+                    class="input input-bordered"
+                    placeholder="rust, leptos, web"
+                    prop:value=tags
+                    on:input=move |ev| set_tags(event_target_value(&ev))
+                />
+            </label>
+            <div class="grid grid-cols-1 md:grid-cols-[1fr_auto] gap-4">
+                <label class="form-control">
+                    <span class="label-text mb-1">"Series"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered"
+                        placeholder="Building a blog with Leptos"
+                        prop:value=series
+                        on:input=move |ev| set_series(event_target_value(&ev))
+                    />
+                </label>
+                <label class="form-control">
+                    <span class="label-text mb-1">"Part"</span>
+                    <input
+                        type="number"
+                        class="input input-bordered"
+                        min="1"
+                        placeholder="Last"
+                        prop:value=series_position
+                        on:input=move |ev| set_series_position(event_target_value(&ev))
+                    />
+                </label>
+            </div>
+            <div class="grid grid-cols-1 lg:grid-cols-2 gap-4">
+                <label class="form-control">
+                    <span class="label-text mb-1">"Content"</span>
+                    <textarea
+                        class="textarea textarea-bordered font-mono h-full"
+                        rows="24"
+                        prop:value=content
+                        on:input=move |ev| set_content(event_target_value(&ev))
+                    ></textarea>
+                </label>
+                <div class="border border-base-300 rounded-lg overflow-auto max-h-[80vh]">
+                    <Transition fallback=move || view! { <div class="p-4">"Rendering..."</div> }>
+                        {move || match preview.get() {
+                            None => EitherOf3::A(view! { <div class="p-4">"Rendering..."</div> }),
+                            Some(Ok(rendered)) => EitherOf3::B(view! {
+                                <PostBody content=rendered.content toc=rendered.toc/>
+                            }),
+                            Some(Err(e)) => EitherOf3::C(view! {
+                                <div class="text-red-500 p-4">"Preview failed: " {e.to_string()}</div>
+                            }),
+                        }}
+                    </Transition>
+                </div>
+            </div>
+            <div class="flex items-center gap-4">
+                <button type="submit" class="btn btn-accent" disabled=move || save.pending().get()>
+                    "Save"
+                </button>
+                {preview_href.map(|href| view! {
+                    <a href=href target="_blank" class="link text-sm">"Preview link"</a>
+                })}
+                {id.map(|id| view! {
+                    <a href=format!("/admin/posts/{id}/revisions") class="link text-sm">"History"</a>
+                })}
+                {move || save.value().get().and_then(Result::err).map(|e| view! {
+                    <span class="text-red-500">{e.to_string()}</span>
+                })}
+            </div>
+        </form>
+    }
+}
@@ -0,0 +1,18 @@
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+use crate::models::tag::Tag;
+
+/// A post's tags, each linking to the posts sharing it.
+#[component]
+pub fn PostTags(tags: Vec<Tag>) -> impl IntoView {
+    view! {
+        <div class="flex flex-wrap gap-2">
+            {tags.into_iter().map(|tag| view! {
+                <A href=tag.href()>
+                    <span class="badge badge-outline hover:badge-accent">{format!("#{}", tag.name)}</span>
+                </A>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
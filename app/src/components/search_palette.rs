@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use leptos::prelude::*;
+use leptos::either::*;
+use leptos::ev::{keydown, KeyboardEvent};
+use leptos::html::Input;
+use leptos_router::{hooks::use_navigate, NavigateOptions};
+use crate::models::search_index::{IndexedPost, SearchIndex};
+
+const MAX_RESULTS: usize = 8;
+
+/// Tags whose focus means the user is typing, so single-key shortcuts such
+/// as `/` must not fire.
+const TEXT_INPUT_TAGS: [&str; 3] = ["INPUT", "TEXTAREA", "SELECT"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum IndexState {
+    NotLoaded,
+    Loading,
+    Loaded(Arc<SearchIndex>),
+    Failed,
+}
+
+/// Whether the search palette is open, provided by `App` so that other
+/// keyboard shortcuts can stand down while the palette has the keyboard.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchPaletteOpen(pub RwSignal<bool>);
+
+/// Instant search over titles, descriptions, headings and categories, opened
+/// with `/` or `Ctrl+K`. The index is fetched the first time the palette
+/// opens and searched entirely in the browser.
+#[component]
+pub fn SearchPalette() -> impl IntoView {
+    let SearchPaletteOpen(is_open) = expect_context();
+    let (query, set_query) = signal(String::new());
+    let (selected_idx, set_selected_idx) = signal(0);
+    let (index, set_index) = signal(IndexState::NotLoaded);
+    let input_ref = NodeRef::<Input>::new();
+    let navigate = use_navigate();
+
+    let results = Memo::new(move |_| match index.get() {
+        IndexState::Loaded(index) => query.with(|query| {
+            index.search(query, MAX_RESULTS).into_iter().cloned().collect::<Vec<_>>()
+        }),
+        _ => Vec::new(),
+    });
+
+    let open = move || {
+        if index.get_untracked() == IndexState::NotLoaded {
+            set_index(IndexState::Loading);
+            load_index(set_index);
+        }
+        set_query(String::new());
+        set_selected_idx(0);
+        is_open.set(true);
+    };
+    let close = move || is_open.set(false);
+
+    let cycle_selection = move |direction: i32| {
+        let len = results.with(Vec::len) as i32;
+        if len > 0 {
+            set_selected_idx((selected_idx.get() as i32 + direction).rem_euclid(len) as usize);
+        }
+    };
+
+    let handle_enter = move || {
+        let selected = results.with(|results| results.get(selected_idx.get()).map(|post| post.slug.clone()));
+        if let Some(slug) = selected {
+            close();
+            navigate(&format!("/blog/{slug}"), NavigateOptions::default());
+        }
+    };
+
+    window_event_listener(keydown, move |e: KeyboardEvent| {
+        let key = e.key();
+        if (e.ctrl_key() || e.meta_key()) && key == "k" {
+            e.prevent_default();
+            if is_open.get_untracked() { close() } else { open() }
+            return;
+        }
+
+        if !is_open.get_untracked() {
+            if key == "/" && !is_typing(&e) {
+                e.prevent_default();
+                open();
+            }
+            return;
+        }
+
+        match key.as_str() {
+            "Escape" => close(),
+            "ArrowDown" => cycle_selection(1),
+            "ArrowUp" => cycle_selection(-1),
+            "Enter" => {
+                e.prevent_default();
+                handle_enter();
+            }
+            _ => (),
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(input) = input_ref.get() {
+            let _ = input.focus();
+        }
+    });
+
+    view! {
+        <Show when=move || is_open.get()>
+            <div class="fixed inset-0 z-50 bg-base-300/60 flex items-start justify-center pt-24 px-4" on:click=move |_| close()>
+                <div
+                    class="bg-base-100 border border-dashed border-accent w-full max-w-xl p-4"
+                    on:click=|e| e.stop_propagation()
+                >
+                    <input
+                        type="search"
+                        class="input input-bordered w-full"
+                        placeholder="Search posts..."
+                        node_ref=input_ref
+                        prop:value=query
+                        on:input=move |e| {
+                            set_query(event_target_value(&e));
+                            set_selected_idx(0);
+                        }
+                    />
+                    <div class="mt-4 space-y-1">
+                        {move || match index.get() {
+                            IndexState::NotLoaded | IndexState::Loading => EitherOf4::A(view! {
+                                <div class="text-sm">"Loading search index..."</div>
+                            }),
+                            IndexState::Failed => EitherOf4::B(view! {
+                                <div class="text-sm text-red-500">"Search is unavailable right now."</div>
+                            }),
+                            IndexState::Loaded(_) if results.with(Vec::is_empty) => EitherOf4::C(view! {
+                                <div class="text-sm">
+                                    {move || if query.with(|query| query.trim().is_empty()) { "Type to search." } else { "No posts found." }}
+                                </div>
+                            }),
+                            IndexState::Loaded(_) => EitherOf4::D(view! {
+                                <For
+                                    each=move || results.get().into_iter().enumerate()
+                                    key=|(idx, post)| (*idx, post.slug.clone())
+                                    children=move |(idx, post)| view! {
+                                        <SearchPaletteResult
+                                            post=post
+                                            selected=Signal::derive(move || selected_idx.get() == idx)
+                                            on_select=move || close()
+                                        />
+                                    }
+                                />
+                            }),
+                        }}
+                    </div>
+                    <div class="mt-4 text-sm">
+                        <span class="font-bold">"↑"</span>" and "<span class="font-bold">"↓"</span>" to move, "
+                        <span class="font-bold">"Enter"</span>" to open, "<span class="font-bold">"Esc"</span>" to close."
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+fn SearchPaletteResult(
+    post: IndexedPost,
+    selected: Signal<bool>,
+    on_select: impl Fn() + 'static,
+) -> impl IntoView {
+    view! {
+        <a href=format!("/blog/{}", post.slug) on:click=move |_| on_select()>
+            <div class=move || if selected.get() {
+                "bg-accent px-2 py-1 cursor-pointer"
+            } else {
+                "px-2 py-1 hover:bg-accent cursor-pointer"
+            }>
+                <div class="font-bold">{post.title}</div>
+                <div class="text-sm truncate">{post.description}</div>
+            </div>
+        </a>
+    }
+}
+
+/// Whether the key was pressed in a text field, so it belongs to the field.
+pub fn is_typing(e: &KeyboardEvent) -> bool {
+    use wasm_bindgen::JsCast;
+
+    e.target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .is_some_and(|element| TEXT_INPUT_TAGS.contains(&element.tag_name().as_str()))
+}
+
+fn load_index(set_index: WriteSignal<IndexState>) {
+    leptos::task::spawn_local(async move {
+        match fetch_index().await {
+            Ok(index) => set_index(IndexState::Loaded(Arc::new(index))),
+            Err(e) => {
+                leptos::logging::error!("failed to load search index: {e}");
+                set_index(IndexState::Failed);
+            }
+        }
+    });
+}
+
+#[cfg(feature = "hydrate")]
+async fn fetch_index() -> Result<SearchIndex, String> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use crate::models::search_index::SEARCH_INDEX_PATH;
+
+    let response = JsFuture::from(window().fetch_with_str(SEARCH_INDEX_PATH))
+        .await
+        .map_err(|e| format!("{e:?}"))?
+        .dyn_into::<web_sys::Response>()
+        .map_err(|e| format!("{e:?}"))?;
+    if !response.ok() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+
+    let buffer = JsFuture::from(response.array_buffer().map_err(|e| format!("{e:?}"))?)
+        .await
+        .map_err(|e| format!("{e:?}"))?;
+    SearchIndex::from_bytes(&js_sys::Uint8Array::new(&buffer).to_vec()).map_err(|e| e.to_string())
+}
+
+/// The palette only opens from browser events, so the server never loads it.
+#[cfg(not(feature = "hydrate"))]
+async fn fetch_index() -> Result<SearchIndex, String> {
+    Err("the search index is only loaded in the browser".to_string())
+}
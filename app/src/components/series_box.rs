@@ -0,0 +1,51 @@
+use leptos::prelude::*;
+use leptos::either::Either;
+use leptos_router::components::A;
+
+use crate::models::series::SeriesWithParts;
+
+/// Where a post stands in its series: "Part N of M" by the parts' stored
+/// numbers, every listed part in order, and buttons to the previous and next
+/// ones. Renders nothing when the post is not among the parts.
+#[component]
+pub fn SeriesBox(series: SeriesWithParts, post_id: i64) -> impl IntoView {
+    let current = series.index_of(post_id)?;
+    let SeriesWithParts { series, parts, last_position } = series;
+    let previous = current.checked_sub(1).and_then(|idx| parts.get(idx)).cloned();
+    let next = parts.get(current + 1).cloned();
+
+    Some(view! {
+        <aside class="border border-dashed border-accent p-4 mb-12">
+            <div class="text-sm">{format!("Part {} of {last_position}", parts[current].position)}</div>
+            <h2 class="text-xl font-bold mb-2">{series.title}</h2>
+            {series.description.map(|description| view! { <p class="mb-2">{description}</p> })}
+            <ol class="list-decimal list-inside mb-4">
+                {parts.into_iter().enumerate().map(|(idx, part)| view! {
+                    <li value=part.position>
+                        {if idx == current {
+                            Either::Left(view! { <span class="font-bold">{part.title}</span> })
+                        } else {
+                            Either::Right(view! { <A href=part.href()><span class="link">{part.title}</span></A> })
+                        }}
+                    </li>
+                }).collect::<Vec<_>>()}
+            </ol>
+            <div class="flex justify-between gap-4">
+                <div>
+                    {previous.map(|part| view! {
+                        <A href=part.href()>
+                            <div class="btn btn-sm">"← Previous"</div>
+                        </A>
+                    })}
+                </div>
+                <div>
+                    {next.map(|part| view! {
+                        <A href=part.href()>
+                            <div class="btn btn-sm">"Next →"</div>
+                        </A>
+                    })}
+                </div>
+            </div>
+        </aside>
+    })
+}
@@ -1,6 +1,106 @@
-use sqlx::PgPool;
-use crate::models::{category::Category, post::BlogPost, post::SqlPost};
+use chrono::Local;
+use sqlx::{PgConnection, PgPool};
+use thiserror::Error;
+use crate::models::{
+    category::{Category, CategoryCount, CategoryFilter, CategoryMatch},
+    post::{
+        count_words, BlogPost, PostCursor, PostForm, PostInput, PostPage, PostSort, PostStatus, PostSummary,
+        RenderError, RenderedMarkdown, SearchResult, SitemapEntry, SqlPost, SqlPostSummary, SqlSearchResult,
+        FORM_DATETIME_FORMAT, HIGHLIGHT_START, HIGHLIGHT_STOP, RENDERER_VERSION,
+    },
+    revision::PostRevision,
+    search_index::IndexSource,
+    series::{Series, SeriesPart, SeriesWithParts},
+    tag::Tag,
+    user::AdminUser,
+};
 
+#[derive(Debug, Error)]
+pub enum RepositoryError {
+    #[error("slug `{0}` is already in use")]
+    DuplicateSlug(String),
+    #[error("post not found")]
+    PostNotFound,
+    #[error("category not found")]
+    CategoryNotFound,
+    #[error("revision not found")]
+    RevisionNotFound,
+    #[error("another post is already that part of the series")]
+    DuplicateSeriesPart,
+    #[error(transparent)]
+    Render(#[from] RenderError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+impl RepositoryError {
+    /// Maps constraint violations from an insert or update of `slug` to
+    /// their typed variants.
+    fn from_write(error: sqlx::Error, slug: &str) -> Self {
+        match &error {
+            sqlx::Error::Database(e) if e.is_unique_violation() => Self::DuplicateSlug(slug.to_string()),
+            sqlx::Error::Database(e) if e.is_foreign_key_violation() => Self::CategoryNotFound,
+            _ => Self::Database(error),
+        }
+    }
+}
+
+/// Runs `sqlx::query_as!` over `blog_posts p` with each post's categories
+/// and tags selected alongside `$columns`; `$rest` carries on after the
+/// `FROM`. `SqlPost` and `SqlPostSummary` select their usual columns, the
+/// former with the cached render joined in as `r`; other records name theirs.
+macro_rules! query_posts {
+    (SqlPost, $rest:literal $(, $arg:expr)* $(,)?) => {
+        query_posts!(
+            @select SqlPost,
+            r#"
+                p.id, p.title, p.description, p.hero_image,
+                p.content, p.published_at, p.updated_at, p.slug,
+                p.word_count,
+                r.content as "rendered_content?", r.toc as "rendered_toc?",
+                r.renderer_version as "rendered_version?","#,
+            "LEFT JOIN post_renders r ON r.blog_post_id = p.id AND r.content_hash = md5(p.content)",
+            $rest $(, $arg)*
+        )
+    };
+    (SqlPostSummary, $rest:literal $(, $arg:expr)* $(,)?) => {
+        query_posts!(
+            @select SqlPostSummary,
+            r#"
+                p.id, p.title, p.description, p.hero_image,
+                p.published_at, p.slug, p.status as "status: PostStatus",
+                p.word_count,"#,
+            "",
+            $rest $(, $arg)*
+        )
+    };
+    ($record:ident, $columns:literal, $rest:literal $(, $arg:expr)* $(,)?) => {
+        query_posts!(@select $record, $columns, "", $rest $(, $arg)*)
+    };
+    (@select $record:ident, $columns:literal, $joins:literal, $rest:literal $(, $arg:expr)*) => {
+        sqlx::query_as!(
+            $record,
+            "SELECT " + $columns + r#"
+                COALESCE(
+                    (SELECT ARRAY_AGG((c.id, c.name, c.slug, c.description) ORDER BY c.name)
+                     FROM blog_posts_categories pc
+                     JOIN categories c ON pc.category_id = c.id
+                     WHERE pc.blog_post_id = p.id),
+                    '{}'
+                ) as "categories!: Vec<Category>",
+                COALESCE(
+                    (SELECT ARRAY_AGG((t.id, t.name, t.slug) ORDER BY t.name)
+                     FROM blog_posts_tags pt
+                     JOIN tags t ON pt.tag_id = t.id
+                     WHERE pt.blog_post_id = p.id),
+                    '{}'
+                ) as "tags!: Vec<Tag>"
+            FROM blog_posts p
+            "# + $joins + $rest
+            $(, $arg)*
+        )
+    };
+}
 
 #[derive(Debug, Clone)]
 pub struct PostRepository(PgPool);
@@ -10,20 +110,42 @@ impl PostRepository {
         Self(pool)
     }
 
-    pub async fn get_all_categories(&self) -> Result<Vec<Category>, sqlx::Error> {
+    /// Returns every category, including empty ones, with how many listed
+    /// posts are filed under it.
+    pub async fn get_all_categories(&self) -> Result<Vec<CategoryCount>, sqlx::Error> {
+        sqlx::query_as!(
+            CategoryCount,
+            r#"
+            SELECT
+                (c.id, c.name, c.slug, c.description) as "category!: Category",
+                COUNT(p.id) as "post_count!"
+            FROM categories c
+            LEFT JOIN blog_posts_categories pc ON c.id = pc.category_id
+            LEFT JOIN blog_posts p ON pc.blog_post_id = p.id
+                AND is_listed(p)
+            GROUP BY c.id
+            ORDER BY c.name ASC
+            "#
+        )
+        .fetch_all(&self.0)
+        .await
+    }
+
+    pub async fn get_category_by_slug(&self, slug: &str) -> Result<Option<Category>, sqlx::Error> {
         sqlx::query_as!(
             Category,
             r#"
             SELECT id, name, slug, description
             FROM categories
-            ORDER BY name ASC
-            "#
+            WHERE slug = $1
+            "#,
+            slug
         )
-        .fetch_all(&self.0)
+        .fetch_optional(&self.0)
         .await
     }
 
-    pub async fn create_category(&self, category: &Category) -> Result<Category, sqlx::Error> {
+    pub async fn create_category(&self, category: &Category) -> Result<Category, RepositoryError> {
         sqlx::query_as!(
             Category,
             r#"
@@ -37,170 +159,973 @@ impl PostRepository {
         )
         .fetch_one(&self.0)
         .await
+        .map_err(|e| RepositoryError::from_write(e, &category.slug))
+    }
+
+    pub async fn update_category(&self, id: i64, category: &Category) -> Result<Category, RepositoryError> {
+        sqlx::query_as!(
+            Category,
+            r#"
+            UPDATE categories
+            SET name = $2, slug = $3, description = $4
+            WHERE id = $1
+            RETURNING id, name, slug, description
+            "#,
+            id,
+            category.name,
+            category.slug,
+            category.description
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|e| RepositoryError::from_write(e, &category.slug))?
+        .ok_or(RepositoryError::CategoryNotFound)
+    }
+
+    pub async fn delete_category(&self, id: i64) -> Result<(), RepositoryError> {
+        let result = sqlx::query!("DELETE FROM categories WHERE id = $1", id)
+            .execute(&self.0)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::CategoryNotFound),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn get_tag_by_slug(&self, slug: &str) -> Result<Option<Tag>, sqlx::Error> {
+        sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT id, name, slug
+            FROM tags
+            WHERE slug = $1
+            "#,
+            slug
+        )
+        .fetch_optional(&self.0)
+        .await
     }
 
-    pub async fn get_all_posts_with_categories(&self) -> Result<Vec<BlogPost>, sqlx::Error> {
-        let posts = sqlx::query_as!(
+    /// Returns the series a post is a part of, with its listed parts in
+    /// order. The post itself is always among them, so previews show where
+    /// an unpublished part will go.
+    pub async fn get_post_series(&self, post_id: i64) -> Result<Option<SeriesWithParts>, sqlx::Error> {
+        let series = sqlx::query_as!(
+            Series,
+            r#"
+            SELECT s.id, s.title, s.slug, s.description
+            FROM series s
+            JOIN blog_posts_series ps ON ps.series_id = s.id
+            WHERE ps.blog_post_id = $1
+            "#,
+            post_id
+        )
+        .fetch_optional(&self.0)
+        .await?;
+
+        match series {
+            Some(series) => Ok(Some(self.with_parts(series, post_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Pairs a series with its listed parts, plus `include_post_id` whatever
+    /// its status.
+    async fn with_parts(&self, series: Series, include_post_id: i64) -> Result<SeriesWithParts, sqlx::Error> {
+        let parts = sqlx::query_as!(
+            SeriesPart,
+            r#"
+            SELECT p.id, p.title, p.slug, ps.position
+            FROM blog_posts_series ps
+            JOIN blog_posts p ON ps.blog_post_id = p.id
+            WHERE ps.series_id = $1
+                AND (is_listed(p) OR p.id = $2)
+            ORDER BY ps.position ASC
+            "#,
+            series.id,
+            include_post_id
+        )
+        .fetch_all(&self.0)
+        .await?;
+
+        let last_position = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(MAX(position), 0) as "last_position!"
+            FROM blog_posts_series
+            WHERE series_id = $1
+            "#,
+            series.id
+        )
+        .fetch_one(&self.0)
+        .await?;
+
+        Ok(SeriesWithParts { series, parts, last_position })
+    }
+
+    /// Returns every listed post: scheduled or published, and already past
+    /// its publish date.
+    pub async fn get_all_posts_with_categories(&self) -> Result<Vec<BlogPost>, RepositoryError> {
+        let posts = query_posts!(
             SqlPost,
             r#"
-            SELECT
-                p.id, p.title, p.description, p.hero_image,
-                p.content, p.published_at, p.slug,
-                ARRAY[]::TEXT[] as "categories!: Vec<Category>"
-            FROM blog_posts p
+            WHERE is_listed(p)
             ORDER BY p.published_at DESC
             "#
         )
         .fetch_all(&self.0)
         .await?;
 
-        let mut posts_with_categories = vec![];
+        self.render_posts(posts).await
+    }
 
-        for mut post in posts {
-            let categories = sqlx::query_as!(
-                Category,
-                r#"
-                SELECT c.id, c.name, c.slug, c.description
-                FROM categories c
-                JOIN blog_posts_categories pc ON c.id = pc.category_id
-                WHERE pc.blog_post_id = $1
-                ORDER BY c.name
-                "#,
-                post.id
-            )
-            .fetch_all(&self.0)
-            .await?;
+    /// Returns the `limit` most recently published listed posts.
+    pub async fn get_latest_posts(&self, limit: i64) -> Result<Vec<BlogPost>, RepositoryError> {
+        let posts = query_posts!(
+            SqlPost,
+            r#"
+            WHERE is_listed(p)
+            ORDER BY p.published_at DESC, p.id DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.0)
+        .await?;
 
-            post.categories = categories;
-            posts_with_categories.push(post.into_post());
-        }
+        self.render_posts(posts).await
+    }
 
-        Ok(posts_with_categories)
+    /// Returns what the instant search index needs of every listed post,
+    /// newest first.
+    pub async fn get_search_index_sources(&self) -> Result<Vec<IndexSource>, sqlx::Error> {
+        query_posts!(
+            IndexSource,
+            "p.slug, p.title, p.description, p.content,",
+            r#"
+            WHERE is_listed(p)
+            ORDER BY p.published_at DESC, p.id DESC
+            "#
+        )
+        .fetch_all(&self.0)
+        .await
     }
 
+    /// Returns the `limit` newest listed posts matching `filter`; any
+    /// listed post when it is empty.
     pub async fn search_posts_by_category(
         &self,
-        category_slug: &str
-    ) -> Result<Vec<BlogPost>, sqlx::Error> {
-        let posts = sqlx::query_as!(
+        filter: &CategoryFilter,
+        limit: i64,
+    ) -> Result<Vec<BlogPost>, RepositoryError> {
+        let posts = query_posts!(
             SqlPost,
             r#"
-            SELECT DISTINCT
+            WHERE is_listed(p)
+              AND (CARDINALITY($1::TEXT[]) = 0 OR (
+                SELECT COUNT(DISTINCT fc.slug)
+                FROM blog_posts_categories fpc
+                JOIN categories fc ON fpc.category_id = fc.id
+                WHERE fpc.blog_post_id = p.id AND fc.slug = ANY($1)
+            ) >= CASE WHEN $2 THEN (SELECT COUNT(DISTINCT slug) FROM UNNEST($1) slug) ELSE 1 END)
+            ORDER BY p.published_at DESC, p.id DESC
+            LIMIT $3
+            "#,
+            &filter.slugs,
+            filter.mode == CategoryMatch::All,
+            limit
+        )
+        .fetch_all(&self.0)
+        .await?;
+
+        self.render_posts(posts).await
+    }
+
+    /// Full-text searches listed posts with web search syntax (quoted
+    /// phrases, `or`, `-word`), best matches first, each with a highlighted
+    /// snippet of its content.
+    pub async fn search(&self, query: &str, limit: i64) -> Result<Vec<SearchResult>, sqlx::Error> {
+        let headline_options = format!(
+            "StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_STOP}, MaxWords=35, MinWords=15, MaxFragments=2"
+        );
+
+        let results = query_posts!(
+            SqlSearchResult,
+            r#"
                 p.id, p.title, p.description, p.hero_image,
-                p.content, p.published_at, p.slug,
-                ARRAY[]::TEXT[] as "categories!: Vec<Category>"
-            FROM blog_posts p
-            JOIN blog_posts_categories pc ON p.id = pc.blog_post_id
-            JOIN categories c ON pc.category_id = c.id
-            WHERE c.slug = $1
-            ORDER BY p.published_at DESC
+                p.published_at, p.slug, p.status as "status: PostStatus",
+                p.word_count,
+                ts_headline('english', p.content, search.query, $2) as "snippet!","#,
+            r#"
+            CROSS JOIN websearch_to_tsquery('english', $1) AS search(query)
+            WHERE is_listed(p)
+              AND p.search_vector @@ search.query
+            ORDER BY ts_rank(p.search_vector, search.query) DESC, p.published_at DESC
+            LIMIT $3
             "#,
-            category_slug
+            query,
+            headline_options,
+            limit
         )
         .fetch_all(&self.0)
         .await?;
 
-        let mut posts_with_categories = vec![];
+        Ok(results.into_iter().map(SqlSearchResult::into_result).collect())
+    }
 
-        for mut post in posts {
-            let categories = sqlx::query_as!(
-                Category,
-                r#"
-                SELECT c.id, c.name, c.slug, c.description
-                FROM categories c
-                JOIN blog_posts_categories pc ON c.id = pc.category_id
-                WHERE pc.blog_post_id = $1
-                ORDER BY c.name
-                "#,
-                post.id
-            )
-            .fetch_all(&self.0)
-            .await?;
+    /// Returns up to `limit` listed post summaries in `sort` order,
+    /// starting strictly after `after` when given, restricted to `filter`
+    /// unless it is empty and to posts tagged `tag_slug` when given. Content
+    /// is never selected, so nothing goes through femark.
+    pub async fn get_posts_page(
+        &self,
+        filter: &CategoryFilter,
+        tag_slug: Option<&str>,
+        sort: PostSort,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<PostPage, sqlx::Error> {
+        let (after_published_at, after_id, after_word_count) = after
+            .map(|cursor| (Some(cursor.published_at), Some(cursor.id), Some(cursor.word_count)))
+            .unwrap_or_default();
 
-            post.categories = categories;
-            posts_with_categories.push(post.into_post());
-        }
+        let mut posts = query_posts!(
+            SqlPostSummary,
+            r#"
+            WHERE is_listed(p)
+              AND (CARDINALITY($1::TEXT[]) = 0 OR (
+                    SELECT COUNT(DISTINCT fc.slug)
+                    FROM blog_posts_categories fpc
+                    JOIN categories fc ON fpc.category_id = fc.id
+                    WHERE fpc.blog_post_id = p.id AND fc.slug = ANY($1)
+                ) >= CASE WHEN $5 THEN (SELECT COUNT(DISTINCT slug) FROM UNNEST($1) slug) ELSE 1 END)
+              AND ($6::TEXT IS NULL OR EXISTS (
+                    SELECT 1
+                    FROM blog_posts_tags fpt
+                    JOIN tags ft ON fpt.tag_id = ft.id
+                    WHERE fpt.blog_post_id = p.id AND ft.slug = $6
+                ))
+              AND ($2::TIMESTAMPTZ IS NULL OR CASE $7
+                    WHEN 'shortest' THEN p.word_count > $8
+                        OR (p.word_count = $8 AND (p.published_at, p.id) < ($2, $3))
+                    WHEN 'longest' THEN (p.word_count, p.published_at, p.id) < ($8, $2, $3)
+                    ELSE (p.published_at, p.id) < ($2, $3)
+                END)
+            ORDER BY
+                CASE WHEN $7 = 'shortest' THEN p.word_count END ASC,
+                CASE WHEN $7 = 'longest' THEN p.word_count END DESC,
+                p.published_at DESC, p.id DESC
+            LIMIT $4
+            "#,
+            &filter.slugs,
+            after_published_at,
+            after_id,
+            limit + 1,
+            filter.mode == CategoryMatch::All,
+            tag_slug,
+            sort.as_str(),
+            after_word_count
+        )
+        .fetch_all(&self.0)
+        .await?;
 
-        Ok(posts_with_categories)
-    }
-
-    // pub async fn create_post_with_categories(
-    //     &self,
-    //     post: &BlogPost,
-    //     category_ids: &[i64],
-    // ) -> Result<BlogPost, sqlx::Error> {
-    //     let mut tx = pool.begin().await?;
-
-    //     // Insert the blog post
-    //     let post = sqlx::query_as!(
-    //         BlogPost,
-    //         r#"
-    //         INSERT INTO blog_posts (title, description, hero_image, content, published_at, slug)
-    //         VALUES ($1, $2, $3, $4, $5, $6)
-    //         RETURNING id, title, description, hero_image, content, published_at, slug,
-    //                   ARRAY[]::TEXT[] as "categories!: Vec<Category>"
-    //         "#,
-    //         post.title,
-    //         post.description,
-    //         post.hero_image,
-    //         post.content,
-    //         post.published_at,
-    //         post.slug
-    //     )
-    //     .fetch_one(&mut *tx)
-    //     .await?;
-
-    //     // Insert category associations
-    //     for &category_id in category_ids {
-    //         sqlx::query!(
-    //             r#"
-    //             INSERT INTO blog_posts_categories (blog_post_id, category_id)
-    //             VALUES ($1, $2)
-    //             "#,
-    //             post.id,
-    //             category_id
-    //         )
-    //         .execute(&mut *tx)
-    //         .await?;
-    //     }
-
-    //     tx.commit().await?;
-
-    //     // Fetch the post with categories
-    //     self.get_post_by_slug(&post.slug).await
-    // }
-
-    pub async fn get_post_by_slug(&self, slug: &str) -> Result<BlogPost, sqlx::Error> {
-        let mut post = sqlx::query_as!(
-            SqlPost,
+        let next_cursor = if posts.len() as i64 > limit {
+            posts.truncate(limit as usize);
+            posts.last().map(|post| post.cursor().encode())
+        } else {
+            None
+        };
+
+        Ok(PostPage {
+            posts: posts.into_iter().map(SqlPostSummary::into_summary).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Returns the listed posts published just before and just after a
+    /// post, in that order.
+    pub async fn get_adjacent_posts(
+        &self,
+        post_id: i64,
+    ) -> Result<(Option<PostSummary>, Option<PostSummary>), sqlx::Error> {
+        let previous = query_posts!(
+            SqlPostSummary,
+            r#"
+            WHERE is_listed(p)
+              AND (p.published_at, p.id) < (SELECT cur.published_at, cur.id FROM blog_posts cur WHERE cur.id = $1)
+            ORDER BY p.published_at DESC, p.id DESC
+            LIMIT 1
+            "#,
+            post_id
+        )
+        .fetch_optional(&self.0)
+        .await?;
+
+        let next = query_posts!(
+            SqlPostSummary,
+            r#"
+            WHERE is_listed(p)
+              AND (p.published_at, p.id) > (SELECT cur.published_at, cur.id FROM blog_posts cur WHERE cur.id = $1)
+            ORDER BY p.published_at ASC, p.id ASC
+            LIMIT 1
+            "#,
+            post_id
+        )
+        .fetch_optional(&self.0)
+        .await?;
+
+        Ok((previous.map(SqlPostSummary::into_summary), next.map(SqlPostSummary::into_summary)))
+    }
+
+    /// Returns up to `limit` listed posts sharing categories or tags with a
+    /// post, those sharing the most first, then newest first.
+    pub async fn get_related_posts(&self, post_id: i64, limit: i64) -> Result<Vec<PostSummary>, sqlx::Error> {
+        let posts = query_posts!(
+            SqlPostSummary,
+            r#"
+            CROSS JOIN LATERAL (
+                SELECT
+                    (SELECT COUNT(*)
+                     FROM blog_posts_categories a
+                     JOIN blog_posts_categories b ON a.category_id = b.category_id
+                     WHERE a.blog_post_id = p.id AND b.blog_post_id = $1)
+                    + (SELECT COUNT(*)
+                     FROM blog_posts_tags a
+                     JOIN blog_posts_tags b ON a.tag_id = b.tag_id
+                     WHERE a.blog_post_id = p.id AND b.blog_post_id = $1) as shared
+            ) r
+            WHERE is_listed(p)
+              AND p.id <> $1 AND r.shared > 0
+            ORDER BY r.shared DESC, p.published_at DESC, p.id DESC
+            LIMIT $2
+            "#,
+            post_id,
+            limit
+        )
+        .fetch_all(&self.0)
+        .await?;
+
+        Ok(posts.into_iter().map(SqlPostSummary::into_summary).collect())
+    }
+
+    /// Returns every post newest first whatever its status, for the admin
+    /// post list.
+    pub async fn get_all_post_summaries(&self) -> Result<Vec<PostSummary>, sqlx::Error> {
+        let posts = query_posts!(
+            SqlPostSummary,
+            r#"
+            ORDER BY p.published_at DESC, p.id DESC
+            "#
+        )
+        .fetch_all(&self.0)
+        .await?;
+
+        Ok(posts.into_iter().map(SqlPostSummary::into_summary).collect())
+    }
+
+    /// Returns the raw, unrendered fields of a post for editing.
+    pub async fn get_post_form(&self, id: i64) -> Result<PostForm, sqlx::Error> {
+        let post = sqlx::query!(
             r#"
             SELECT
-                p.id, p.title, p.description, p.hero_image,
-                p.content, p.published_at, p.slug,
-                ARRAY[]::TEXT[] as "categories!: Vec<Category>"
+                p.id, p.title, p.slug, p.description, p.hero_image,
+                p.content, p.published_at, p.status as "status: PostStatus", p.preview_token,
+                COALESCE(
+                    ARRAY_AGG(pc.category_id) FILTER (WHERE pc.category_id IS NOT NULL),
+                    '{}'
+                ) as "category_ids!: Vec<i64>",
+                COALESCE(
+                    (SELECT ARRAY_AGG(t.name ORDER BY t.name)
+                     FROM blog_posts_tags pt
+                     JOIN tags t ON pt.tag_id = t.id
+                     WHERE pt.blog_post_id = p.id),
+                    '{}'
+                ) as "tags!: Vec<String>",
+                (SELECT s.title
+                 FROM blog_posts_series ps
+                 JOIN series s ON ps.series_id = s.id
+                 WHERE ps.blog_post_id = p.id) as "series_title?",
+                (SELECT ps.position
+                 FROM blog_posts_series ps
+                 WHERE ps.blog_post_id = p.id) as "series_position?"
             FROM blog_posts p
-            WHERE p.slug = $1
+            LEFT JOIN blog_posts_categories pc ON p.id = pc.blog_post_id
+            WHERE p.id = $1
+            GROUP BY p.id
             "#,
-            slug
+            id
         )
         .fetch_one(&self.0)
         .await?;
 
-        let categories = sqlx::query_as!(
-            Category,
+        Ok(PostForm {
+            id: Some(post.id),
+            title: post.title,
+            slug: post.slug,
+            description: post.description,
+            hero_image: post.hero_image,
+            content: post.content,
+            published_at: post.published_at.with_timezone(&Local).format(FORM_DATETIME_FORMAT).to_string(),
+            status: post.status,
+            category_ids: post.category_ids,
+            tags: post.tags,
+            series: post.series_title.unwrap_or_default(),
+            series_position: post.series_position,
+            preview_token: Some(post.preview_token),
+        })
+    }
+
+    /// Inserts a post and returns its id.
+    pub async fn create_post(&self, post: &PostInput) -> Result<i64, RepositoryError> {
+        let mut tx = self.0.begin().await?;
+
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO blog_posts (title, description, hero_image, content, published_at, slug, status, word_count)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+            post.title,
+            post.description,
+            post.hero_image,
+            post.content,
+            post.published_at,
+            post.slug,
+            post.status as PostStatus,
+            count_words(&post.content)
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::from_write(e, &post.slug))?;
+
+        Self::link_categories(&mut tx, id, &post.category_ids).await?;
+        Self::link_tags(&mut tx, id, &post.tag_names).await?;
+        if let Some(title) = &post.series_title {
+            Self::link_series(&mut tx, id, title, post.series_position).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    /// Updates a post, first snapshotting its current text as a revision and
+    /// recording its old slug when it changes. Returns `id` back.
+    pub async fn update_post(&self, id: i64, post: &PostInput) -> Result<i64, RepositoryError> {
+        let mut tx = self.0.begin().await?;
+
+        Self::snapshot_revision(&mut tx, id).await?;
+        Self::record_slug_change(&mut tx, id, &post.slug).await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE blog_posts
+            SET title = $2, description = $3, hero_image = $4,
+                content = $5, published_at = $6, slug = $7, status = $8,
+                word_count = $9, updated_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            post.title,
+            post.description,
+            post.hero_image,
+            post.content,
+            post.published_at,
+            post.slug,
+            post.status as PostStatus,
+            count_words(&post.content)
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::from_write(e, &post.slug))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::PostNotFound);
+        }
+
+        sqlx::query!("DELETE FROM blog_posts_categories WHERE blog_post_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM blog_posts_tags WHERE blog_post_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM blog_posts_series WHERE blog_post_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM post_renders WHERE blog_post_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::link_categories(&mut tx, id, &post.category_ids).await?;
+        Self::link_tags(&mut tx, id, &post.tag_names).await?;
+        if let Some(title) = &post.series_title {
+            Self::link_series(&mut tx, id, title, post.series_position).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    pub async fn delete_post(&self, id: i64) -> Result<(), RepositoryError> {
+        let result = sqlx::query!("DELETE FROM blog_posts WHERE id = $1", id)
+            .execute(&self.0)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::PostNotFound),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the revisions of a post, newest first.
+    pub async fn get_revisions(&self, post_id: i64) -> Result<Vec<PostRevision>, sqlx::Error> {
+        let revisions = sqlx::query!(
             r#"
-            SELECT c.id, c.name, c.slug, c.description
+            SELECT id, title, description, content, created_at
+            FROM blog_post_revisions
+            WHERE blog_post_id = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+            post_id
+        )
+        .fetch_all(&self.0)
+        .await?;
+
+        Ok(revisions
+            .into_iter()
+            .map(|revision| PostRevision {
+                id: revision.id,
+                title: revision.title,
+                description: revision.description,
+                content: revision.content,
+                created_at: revision.created_at.with_timezone(&Local).format("%d/%m/%Y %H:%M").to_string(),
+            })
+            .collect())
+    }
+
+    /// Copies a revision's text back onto its post. The text being replaced
+    /// is snapshotted first, so a restore can itself be undone.
+    pub async fn restore_revision(&self, post_id: i64, revision_id: i64) -> Result<(), RepositoryError> {
+        let mut tx = self.0.begin().await?;
+
+        Self::snapshot_revision(&mut tx, post_id).await?;
+
+        let content = sqlx::query_scalar!(
+            r#"
+            UPDATE blog_posts p
+            SET title = r.title, description = r.description, content = r.content,
+                updated_at = NOW()
+            FROM blog_post_revisions r
+            WHERE p.id = $1 AND r.id = $2 AND r.blog_post_id = p.id
+            RETURNING p.content
+            "#,
+            post_id,
+            revision_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(RepositoryError::RevisionNotFound)?;
+
+        sqlx::query!("UPDATE blog_posts SET word_count = $2 WHERE id = $1", post_id, count_words(&content))
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM post_renders WHERE blog_post_id = $1", post_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns every listed post with the time it was published or last
+    /// edited, whichever is later.
+    pub async fn get_post_sitemap_entries(&self) -> Result<Vec<SitemapEntry>, sqlx::Error> {
+        sqlx::query_as!(
+            SitemapEntry,
+            r#"
+            SELECT slug, GREATEST(published_at, updated_at) as lastmod
+            FROM blog_posts p
+            WHERE is_listed(p)
+            ORDER BY published_at DESC, id DESC
+            "#
+        )
+        .fetch_all(&self.0)
+        .await
+    }
+
+    /// Returns every category with the latest change among its listed
+    /// posts, `None` when it has none.
+    pub async fn get_category_sitemap_entries(&self) -> Result<Vec<SitemapEntry>, sqlx::Error> {
+        sqlx::query_as!(
+            SitemapEntry,
+            r#"
+            SELECT c.slug, MAX(GREATEST(p.published_at, p.updated_at)) as lastmod
             FROM categories c
-            JOIN blog_posts_categories pc ON c.id = pc.category_id
-            WHERE pc.blog_post_id = $1
+            LEFT JOIN blog_posts_categories pc ON c.id = pc.category_id
+            LEFT JOIN blog_posts p ON pc.blog_post_id = p.id
+                AND is_listed(p)
+            GROUP BY c.id
             ORDER BY c.name
-            "#,
-            post.id
+            "#
         )
         .fetch_all(&self.0)
+        .await
+    }
+
+    /// Returns the current slug of the publicly visible post that used to be
+    /// published under `old_slug`, if any.
+    pub async fn get_current_slug(&self, old_slug: &str) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT p.slug
+            FROM post_slug_history h
+            JOIN blog_posts p ON p.id = h.blog_post_id
+            WHERE h.slug = $1 AND p.status <> 'draft' AND p.published_at <= NOW()
+            "#,
+            old_slug
+        )
+        .fetch_optional(&self.0)
+        .await
+    }
+
+    async fn record_slug_change(conn: &mut PgConnection, post_id: i64, new_slug: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO post_slug_history (slug, blog_post_id)
+            SELECT slug, id
+            FROM blog_posts
+            WHERE id = $1 AND slug <> $2
+            ON CONFLICT (slug) DO UPDATE SET blog_post_id = EXCLUDED.blog_post_id, created_at = NOW()
+            "#,
+            post_id,
+            new_slug
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        // A post taking a slug back must not redirect away from itself.
+        sqlx::query!("DELETE FROM post_slug_history WHERE slug = $1", new_slug)
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn snapshot_revision(conn: &mut PgConnection, post_id: i64) -> Result<(), RepositoryError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO blog_post_revisions (blog_post_id, title, description, content)
+            SELECT id, title, description, content
+            FROM blog_posts
+            WHERE id = $1
+            "#,
+            post_id
+        )
+        .execute(conn)
+        .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::PostNotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn link_categories(
+        conn: &mut PgConnection,
+        post_id: i64,
+        category_ids: &[i64],
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO blog_posts_categories (blog_post_id, category_id)
+            SELECT DISTINCT $1::BIGINT, UNNEST($2::BIGINT[])
+            "#,
+            post_id,
+            category_ids
+        )
+        .execute(conn)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => RepositoryError::CategoryNotFound,
+            _ => RepositoryError::Database(e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Attaches the named tags to a post, creating the ones that do not
+    /// exist yet. Names are matched by slug, so existing tags keep the
+    /// spelling they were created with.
+    async fn link_tags(conn: &mut PgConnection, post_id: i64, names: &[String]) -> Result<(), sqlx::Error> {
+        let slugs = names.iter().map(|name| Tag::slugify(name)).collect::<Vec<_>>();
+
+        sqlx::query!(
+            r#"
+            WITH input AS (
+                SELECT DISTINCT ON (slug) name, slug
+                FROM UNNEST($2::TEXT[], $3::TEXT[]) AS input(name, slug)
+            ),
+            upserted AS (
+                INSERT INTO tags (name, slug)
+                SELECT name, slug FROM input
+                ON CONFLICT (slug) DO UPDATE SET slug = EXCLUDED.slug
+                RETURNING id
+            )
+            INSERT INTO blog_posts_tags (blog_post_id, tag_id)
+            SELECT $1, id FROM upserted
+            "#,
+            post_id,
+            names,
+            &slugs
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Makes a post part `position` of the titled series, creating the
+    /// series if it does not exist yet, or its last part when `position` is
+    /// `None`. Titles are matched by slug, like tag names.
+    async fn link_series(
+        conn: &mut PgConnection,
+        post_id: i64,
+        title: &str,
+        position: Option<i32>,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query!(
+            r#"
+            WITH upserted AS (
+                INSERT INTO series (title, slug)
+                VALUES ($2, $3)
+                ON CONFLICT (slug) DO UPDATE SET slug = EXCLUDED.slug
+                RETURNING id
+            )
+            INSERT INTO blog_posts_series (blog_post_id, series_id, position)
+            SELECT $1, id, COALESCE(
+                $4,
+                (SELECT COALESCE(MAX(ps.position), 0) + 1 FROM blog_posts_series ps WHERE ps.series_id = upserted.id)
+            )
+            FROM upserted
+            "#,
+            post_id,
+            title,
+            Tag::slugify(title),
+            position
+        )
+        .execute(conn)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => RepositoryError::DuplicateSeriesPart,
+            _ => RepositoryError::Database(e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Builds posts like `render_post`, leaving out the ones whose markdown
+    /// fails to render, so one broken post does not take down a listing or
+    /// feed.
+    async fn render_posts(&self, posts: Vec<SqlPost>) -> Result<Vec<BlogPost>, RepositoryError> {
+        let mut rendered = Vec::with_capacity(posts.len());
+        for post in posts {
+            let slug = post.slug.clone();
+            match self.render_post(post).await {
+                Ok(post) => rendered.push(post),
+                Err(RepositoryError::Render(e)) => leptos::logging::error!("skipping post {slug}: {e}"),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(rendered)
+    }
+
+    /// Builds a post from its cached HTML, rendering its markdown and
+    /// caching the result when the cache is missing or stale.
+    async fn render_post(&self, mut post: SqlPost) -> Result<BlogPost, RepositoryError> {
+        if let Some(rendered) = post.take_cached_render() {
+            return Ok(post.into_post(rendered));
+        }
+
+        let rendered = RenderedMarkdown::render(&post.content)?;
+        sqlx::query!(
+            r#"
+            INSERT INTO post_renders (blog_post_id, content_hash, renderer_version, content, toc)
+            VALUES ($1, md5($2), $3, $4, $5)
+            ON CONFLICT (blog_post_id) DO UPDATE
+            SET content_hash = EXCLUDED.content_hash, renderer_version = EXCLUDED.renderer_version,
+                content = EXCLUDED.content, toc = EXCLUDED.toc
+            "#,
+            post.id,
+            post.content,
+            RENDERER_VERSION,
+            rendered.content,
+            rendered.toc
+        )
+        .execute(&self.0)
+        .await?;
+
+        Ok(post.into_post(rendered))
+    }
+
+    pub async fn get_post_by_id(&self, id: i64) -> Result<BlogPost, RepositoryError> {
+        let post = query_posts!(
+            SqlPost,
+            r#"
+            WHERE p.id = $1
+            "#,
+            id
+        )
+        .fetch_one(&self.0)
+        .await?;
+
+        self.render_post(post).await
+    }
+
+    /// Returns a post by slug if it is publicly reachable: anything but a
+    /// draft, once past its publish date. `None` means there is no such post.
+    pub async fn get_post_by_slug(&self, slug: &str) -> Result<Option<BlogPost>, RepositoryError> {
+        let post = query_posts!(
+            SqlPost,
+            r#"
+            WHERE p.slug = $1 AND p.status <> 'draft' AND p.published_at <= NOW()
+            "#,
+            slug
+        )
+        .fetch_optional(&self.0)
+        .await?;
+
+        match post {
+            Some(post) => Ok(Some(self.render_post(post).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the summary of the post at `slug` under the same rules as
+    /// `get_post_by_slug`, without rendering its content.
+    pub async fn get_post_summary_by_slug(&self, slug: &str) -> Result<Option<PostSummary>, sqlx::Error> {
+        let post = query_posts!(
+            SqlPostSummary,
+            r#"
+            WHERE p.slug = $1 AND p.status <> 'draft' AND p.published_at <= NOW()
+            "#,
+            slug
+        )
+        .fetch_optional(&self.0)
+        .await?;
+
+        Ok(post.map(SqlPostSummary::into_summary))
+    }
+
+    /// Returns a post by slug whatever its status, provided `preview_token`
+    /// matches the post's secret preview token.
+    pub async fn get_post_preview(&self, slug: &str, preview_token: &str) -> Result<Option<BlogPost>, RepositoryError> {
+        let post = query_posts!(
+            SqlPost,
+            r#"
+            WHERE p.slug = $1 AND p.preview_token = $2
+            "#,
+            slug,
+            preview_token
+        )
+        .fetch_optional(&self.0)
         .await?;
 
-        post.categories = categories;
-        Ok(post.into_post())
+        match post {
+            Some(post) => Ok(Some(self.render_post(post).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthRepository(PgPool);
+
+impl AuthRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self(pool)
+    }
+
+    /// Creates the admin account, or replaces its password hash if it
+    /// already exists.
+    pub async fn upsert_admin(&self, username: &str, password_hash: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO admin_users (username, password_hash)
+            VALUES ($1, $2)
+            ON CONFLICT (username) DO UPDATE SET password_hash = EXCLUDED.password_hash
+            "#,
+            username,
+            password_hash
+        )
+        .execute(&self.0)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the admin with `username` along with their password hash.
+    pub async fn get_admin_credentials(
+        &self,
+        username: &str,
+    ) -> Result<Option<(AdminUser, String)>, sqlx::Error> {
+        let user = sqlx::query!(
+            r#"
+            SELECT id, username, password_hash
+            FROM admin_users
+            WHERE username = $1
+            "#,
+            username
+        )
+        .fetch_optional(&self.0)
+        .await?;
+
+        Ok(user.map(|user| (AdminUser { id: user.id, username: user.username }, user.password_hash)))
+    }
+
+    /// Stores a session for `user_id` valid for `ttl_seconds`, sweeping
+    /// expired sessions at the same time.
+    pub async fn create_session(&self, token: &str, user_id: i64, ttl_seconds: i64) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM admin_sessions WHERE expires_at < NOW()")
+            .execute(&self.0)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO admin_sessions (token, user_id, expires_at)
+            VALUES ($1, $2, NOW() + make_interval(secs => $3))
+            "#,
+            token,
+            user_id,
+            ttl_seconds as f64
+        )
+        .execute(&self.0)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_session(&self, token: &str) -> Result<Option<AdminUser>, sqlx::Error> {
+        sqlx::query_as!(
+            AdminUser,
+            r#"
+            SELECT u.id, u.username
+            FROM admin_sessions s
+            JOIN admin_users u ON s.user_id = u.id
+            WHERE s.token = $1 AND s.expires_at > NOW()
+            "#,
+            token
+        )
+        .fetch_optional(&self.0)
+        .await
+    }
+
+    pub async fn delete_session(&self, token: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM admin_sessions WHERE token = $1", token)
+            .execute(&self.0)
+            .await?;
+
+        Ok(())
     }
 }
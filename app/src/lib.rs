@@ -5,16 +5,28 @@ pub mod models;
 pub mod state;
 #[cfg(feature = "ssr")]
 pub mod db;
+#[cfg(feature = "ssr")]
+pub mod auth;
+#[cfg(feature = "ssr")]
+pub mod cache;
 
 use leptos::prelude::*;
 use leptos_meta::*;
 use leptos_router::components::*;
-use leptos_router::path;
+use leptos_router::{path, SsrMode};
 use components::bottom_nav::BottomNav;
+use components::search_palette::{SearchPalette, SearchPaletteOpen};
 use routes::blog_list::BlogList;
 use routes::blog_post::BlogPost;
+use routes::category::CategoryPage;
+use routes::tag::TagPage;
+use routes::search::SearchPage;
 use routes::home::*;
 use routes::page_not_found::PageNotFound;
+use routes::admin::{AdminLayout, AdminLogin, AdminPostEditor, AdminPostList, AdminPostRevisions};
+
+pub const SITE_TITLE: &str = "Hi, I'm Khánh.";
+pub const SITE_AUTHOR: &str = "Khánh";
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
@@ -23,6 +35,9 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
             <head>
                 <meta charset="utf-8" />
                 <meta name="viewport" content="width=device-width, initial-scale=1" />
+                <link rel="alternate" type="application/rss+xml" title="RSS" href="/feed.xml" />
+                <link rel="alternate" type="application/atom+xml" title="Atom" href="/atom.xml" />
+                <link rel="alternate" type="application/feed+json" title="JSON Feed" href="/feed.json" />
                 <AutoReload options=options.clone() />
                 <HydrationScripts options />
                 <MetaTags />
@@ -37,12 +52,13 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
+    provide_context(SearchPaletteOpen(RwSignal::new(false)));
     let (is_routing, set_is_routing) = signal(false);
 
     view! {
         <Stylesheet id="leptos" href="/pkg/blog.css"/>
 
-        <Title text="Hi, I'm Khánh."/>
+        <Title text=SITE_TITLE/>
 
         <Router set_is_routing>
             <main>
@@ -58,9 +74,51 @@ pub fn App() -> impl IntoView {
                     <Route
                         path=path!("/blog/:slug")
                         view=BlogPost
+                        ssr=SsrMode::Async
+                    />
+                    <Route
+                        path=path!("/blog/category/:slug")
+                        view=CategoryPage
+                        ssr=SsrMode::Async
+                    />
+                    <Route
+                        path=path!("/blog/tag/:slug")
+                        view=TagPage
+                        ssr=SsrMode::Async
+                    />
+                    <Route
+                        path=path!("/search")
+                        view=SearchPage
+                    />
+                    <Route
+                        path=path!("/admin/login")
+                        view=AdminLogin
                     />
+                    <ParentRoute
+                        path=path!("/admin")
+                        view=AdminLayout
+                        ssr=SsrMode::Async
+                    >
+                        <Route
+                            path=path!("")
+                            view=AdminPostList
+                        />
+                        <Route
+                            path=path!("posts/new")
+                            view=AdminPostEditor
+                        />
+                        <Route
+                            path=path!("posts/:id")
+                            view=AdminPostEditor
+                        />
+                        <Route
+                            path=path!("posts/:id/revisions")
+                            view=AdminPostRevisions
+                        />
+                    </ParentRoute>
                 </Routes>
             </main>
+            <SearchPalette/>
             <BottomNav is_routing=is_routing/>
         </Router>
     }
@@ -0,0 +1,10 @@
+use serde::{Serialize, Deserialize};
+
+/// Usage of one of the server's read caches since it started.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheStats {
+    pub name: String,
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use crate::models::post::PostSort;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "ssr", derive(sqlx::FromRow, sqlx::Type))]
@@ -8,3 +9,161 @@ pub struct Category {
     pub slug: String,
     pub description: Option<String>,
 }
+
+impl Category {
+    /// The category's landing page.
+    pub fn href(&self) -> String {
+        format!("/blog/category/{}", self.slug)
+    }
+}
+
+/// A category with the number of listed posts filed under it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CategoryCount {
+    pub category: Category,
+    pub post_count: i64,
+}
+
+/// Whether a post must be filed under all of the selected categories or
+/// just one of them.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryMatch {
+    All,
+    #[default]
+    Any,
+}
+
+impl CategoryMatch {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CategoryMatch::All => "all",
+            CategoryMatch::Any => "any",
+        }
+    }
+
+    pub fn parse(mode: &str) -> Option<Self> {
+        [CategoryMatch::All, CategoryMatch::Any].into_iter().find(|m| m.as_str() == mode)
+    }
+}
+
+/// Categories the blog list is narrowed to, as in `?category=a,b&match=all`.
+/// No slugs means every post.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct CategoryFilter {
+    #[serde(default)]
+    pub slugs: Vec<String>,
+    #[serde(default)]
+    pub mode: CategoryMatch,
+}
+
+impl CategoryFilter {
+    pub fn single(slug: String) -> Self {
+        Self { slugs: vec![slug], mode: CategoryMatch::Any }
+    }
+
+    /// Reads the `category` and `match` query parameters, ignoring empty
+    /// and repeated slugs and unknown match modes.
+    pub fn from_query(category: Option<String>, mode: Option<String>) -> Self {
+        let mut slugs: Vec<String> = Vec::new();
+        for slug in category.iter().flat_map(|category| category.split(',')).map(str::trim) {
+            if !slug.is_empty() && !slugs.iter().any(|s| s == slug) {
+                slugs.push(slug.to_string());
+            }
+        }
+
+        Self {
+            slugs,
+            mode: mode.as_deref().and_then(CategoryMatch::parse).unwrap_or_default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slugs.is_empty()
+    }
+
+    pub fn contains(&self, slug: &str) -> bool {
+        self.slugs.iter().any(|s| s == slug)
+    }
+
+    /// The same filter with `slug` selected, or deselected if it already was.
+    pub fn toggle(&self, slug: &str) -> Self {
+        let mut filter = self.clone();
+        if self.contains(slug) {
+            filter.slugs.retain(|s| s != slug);
+        } else {
+            filter.slugs.push(slug.to_string());
+        }
+        filter
+    }
+
+    pub fn with_mode(&self, mode: CategoryMatch) -> Self {
+        Self { slugs: self.slugs.clone(), mode }
+    }
+
+    /// Query parameters selecting this filter in `sort` order; `match` and
+    /// `sort` are left out when they are the default.
+    pub fn query_params(&self, sort: PostSort) -> Vec<String> {
+        let mut params = vec![];
+        if !self.is_empty() {
+            params.push(format!("category={}", self.slugs.join(",")));
+            if self.mode != CategoryMatch::default() {
+                params.push(format!("match={}", self.mode.as_str()));
+            }
+        }
+        if sort != PostSort::default() {
+            params.push(format!("sort={}", sort.as_str()));
+        }
+        params
+    }
+
+    /// `/blog` narrowed to this filter, in `sort` order.
+    pub fn href(&self, sort: PostSort) -> String {
+        let params = self.query_params(sort);
+        if params.is_empty() {
+            "/blog".to_string()
+        } else {
+            format!("/blog?{}", params.join("&"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CategoryFilter, CategoryMatch};
+    use crate::models::post::PostSort;
+
+    fn from_query(category: Option<&str>, mode: Option<&str>) -> CategoryFilter {
+        CategoryFilter::from_query(category.map(str::to_string), mode.map(str::to_string))
+    }
+
+    #[test]
+    fn from_query_reads_match_mode() {
+        assert_eq!(from_query(Some("rust"), Some("all")).mode, CategoryMatch::All);
+        assert_eq!(from_query(Some("rust"), Some("any")).mode, CategoryMatch::Any);
+        assert_eq!(from_query(Some("rust"), None).mode, CategoryMatch::Any);
+        assert_eq!(from_query(Some("rust"), Some("ALL")).mode, CategoryMatch::Any);
+        assert_eq!(from_query(Some("rust"), Some("")).mode, CategoryMatch::Any);
+    }
+
+    #[test]
+    fn from_query_skips_empty_and_repeated_slugs() {
+        assert_eq!(from_query(Some(" rust,,design , rust,"), None).slugs, ["rust", "design"]);
+        assert!(from_query(Some(",, ,"), Some("all")).is_empty());
+        assert!(from_query(None, Some("all")).is_empty());
+    }
+
+    #[test]
+    fn href_leaves_out_default_match_mode_and_sort() {
+        let filter = from_query(Some("rust,design"), Some("all"));
+        assert_eq!(filter.href(PostSort::Newest), "/blog?category=rust,design&match=all");
+        assert_eq!(from_query(Some("rust,design"), Some("any")).href(PostSort::Newest), "/blog?category=rust,design");
+    }
+
+    #[test]
+    fn href_keeps_sort() {
+        let filter = from_query(Some("rust"), None);
+        assert_eq!(filter.toggle("design").href(PostSort::Longest), "/blog?category=rust,design&sort=longest");
+        assert_eq!(filter.toggle("rust").href(PostSort::Shortest), "/blog?sort=shortest");
+    }
+}
@@ -1,3 +1,9 @@
 pub mod post;
 pub mod category;
 pub mod flappy_bird;
+pub mod user;
+pub mod revision;
+pub mod search_index;
+pub mod tag;
+pub mod series;
+pub mod cache;
@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 use cfg_if::cfg_if;
-use crate::models::category::Category;
+use crate::models::{category::Category, series::SeriesWithParts, tag::Tag};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BlogPost {
@@ -11,15 +11,225 @@ pub struct BlogPost {
     pub content: String,
     pub toc: Option<String>,
     pub published_at: String,
+    /// `published_at` in RFC 3339, for feeds and metadata.
+    pub published_at_rfc3339: String,
+    /// Last edit time in RFC 3339.
+    pub updated_at_rfc3339: String,
+    pub word_count: i32,
+    pub reading_time_minutes: i32,
     pub slug: String,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub categories: Vec<Category>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+    /// The series the post is a part of, filled in for the post page only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<SeriesWithParts>,
+    /// The listed posts published just before and after this one, filled
+    /// in for the post page only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<PostSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<PostSummary>,
+    /// Listed posts sharing the most categories and tags with this one,
+    /// filled in for the post page only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<PostSummary>,
+    /// Public origin of the site, for absolute URLs in the post's metadata,
+    /// filled in for the post page only.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub site_url: String,
+}
+
+/// Publication state of a post. Only `Scheduled` and `Published` posts are
+/// listed, and only once their `published_at` has passed; `Archived` posts
+/// stay reachable by slug but are no longer listed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "ssr", derive(sqlx::Type))]
+#[cfg_attr(feature = "ssr", sqlx(type_name = "post_status", rename_all = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    #[default]
+    Draft,
+    Scheduled,
+    Published,
+    Archived,
+}
+
+impl PostStatus {
+    pub const ALL: [PostStatus; 4] = [
+        PostStatus::Draft,
+        PostStatus::Scheduled,
+        PostStatus::Published,
+        PostStatus::Archived,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostStatus::Draft => "draft",
+            PostStatus::Scheduled => "scheduled",
+            PostStatus::Published => "published",
+            PostStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == status)
+    }
+}
+
+/// Order of a post listing. Equally long posts are listed newest first.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum PostSort {
+    #[default]
+    Newest,
+    Shortest,
+    Longest,
+}
+
+impl PostSort {
+    pub const ALL: [PostSort; 3] = [PostSort::Newest, PostSort::Shortest, PostSort::Longest];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostSort::Newest => "newest",
+            PostSort::Shortest => "shortest",
+            PostSort::Longest => "longest",
+        }
+    }
+
+    pub fn parse(sort: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == sort)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PostSort::Newest => "Newest",
+            PostSort::Shortest => "Quickest reads",
+            PostSort::Longest => "Longest reads",
+        }
+    }
+}
+
+/// Listing view of a post: everything `PostCard` shows, without the content.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PostSummary {
+    pub id: i64,
+    pub title: String,
+    pub description: String,
+    pub hero_image: String,
+    pub published_at: String,
+    pub slug: String,
+    pub status: PostStatus,
+    pub word_count: i32,
+    pub reading_time_minutes: i32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<Category>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+}
+
+/// Editable fields of a post as exchanged with the admin editor. `content`
+/// is the raw markdown and `published_at` a `datetime-local` input value.
+/// `preview_token` is read-only and ignored when saving.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PostForm {
+    pub id: Option<i64>,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub hero_image: String,
+    pub content: String,
+    pub published_at: String,
+    pub status: PostStatus,
+    #[serde(default)]
+    pub category_ids: Vec<i64>,
+    /// Names of the post's tags, new ones included.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Title of the series the post is a part of, empty for none.
+    #[serde(default)]
+    pub series: String,
+    /// The post's part number in `series`; `None` appends it as the last part.
+    #[serde(default)]
+    pub series_position: Option<i32>,
+    #[serde(default)]
+    pub preview_token: Option<String>,
+}
+
+/// Markdown rendered to HTML, as shown on the post page.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RenderedMarkdown {
+    pub content: String,
+    pub toc: Option<String>,
+}
+
+/// A full-text search hit. `snippet` is HTML-escaped content with the
+/// matched terms wrapped in `<mark>`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchResult {
+    pub post: PostSummary,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PostPage {
+    pub posts: Vec<PostSummary>,
+    /// Opaque cursor for the following page, `None` on the last page.
+    pub next_cursor: Option<String>,
 }
 
 cfg_if! {
     if #[cfg(feature = "ssr")] {
         use femark::HTMLOutput;
-        use chrono::{DateTime, Local};
+        use chrono::{DateTime, Local, NaiveDateTime, Utc};
+        use crate::models::{series::MAX_SERIES_TITLE_LENGTH, tag::MAX_TAG_LENGTH};
+
+        pub const FORM_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+        /// Version of the HTML cached in `post_renders`. Bump it whenever
+        /// femark or the way posts are rendered changes, so every post is
+        /// rendered again.
+        pub const RENDERER_VERSION: i32 = 1;
+
+        /// Markdown femark could not turn into HTML.
+        #[derive(Debug, thiserror::Error)]
+        #[error("failed to render markdown: {0}")]
+        pub struct RenderError(String);
+
+        /// Reading speed the reading time estimates assume.
+        const WORDS_PER_MINUTE: i32 = 200;
+
+        /// Words of prose in a markdown document: its text, link text and
+        /// inline code, leaving out code blocks, images, raw HTML and markup.
+        pub fn count_words(markdown: &str) -> i32 {
+            use pulldown_cmark::{Event, Parser, Tag as MarkdownTag};
+
+            let mut text = String::new();
+            let mut skipped = 0;
+            for event in Parser::new(markdown) {
+                match event {
+                    Event::Start(MarkdownTag::CodeBlock(_) | MarkdownTag::Image(..)) => skipped += 1,
+                    Event::End(MarkdownTag::CodeBlock(_) | MarkdownTag::Image(..)) => skipped -= 1,
+                    Event::Text(words) | Event::Code(words) if skipped == 0 => text.push_str(&words),
+                    // Inline markup may split a word, so only blocks and
+                    // line breaks separate words.
+                    Event::Start(MarkdownTag::Emphasis | MarkdownTag::Strong | MarkdownTag::Strikethrough | MarkdownTag::Link(..))
+                    | Event::End(MarkdownTag::Emphasis | MarkdownTag::Strong | MarkdownTag::Strikethrough | MarkdownTag::Link(..)) => (),
+                    Event::Start(_) | Event::End(_) | Event::SoftBreak | Event::HardBreak => text.push(' '),
+                    _ => (),
+                }
+            }
+
+            text.split_whitespace().count() as i32
+        }
+
+        /// Minutes it takes to read `word_count` words, rounded up, and at
+        /// least one.
+        pub fn reading_time_minutes(word_count: i32) -> i32 {
+            ((word_count + WORDS_PER_MINUTE - 1) / WORDS_PER_MINUTE).max(1)
+        }
 
         #[derive(sqlx::FromRow, sqlx::Type)]
         pub struct SqlPost{
@@ -29,25 +239,305 @@ cfg_if! {
             pub hero_image: String,
             pub content: String,
             pub published_at: DateTime<Local>,
+            pub updated_at: DateTime<Local>,
+            pub slug: String,
+            pub word_count: i32,
+            pub categories: Vec<Category>,
+            pub tags: Vec<Tag>,
+            /// Cached render of `content`, if there is one for its hash.
+            pub rendered_content: Option<String>,
+            pub rendered_toc: Option<String>,
+            pub rendered_version: Option<i32>,
+        }
+
+        #[derive(sqlx::FromRow, sqlx::Type)]
+        pub struct SqlPostSummary {
+            pub id: i64,
+            pub title: String,
+            pub description: String,
+            pub hero_image: String,
+            pub published_at: DateTime<Local>,
             pub slug: String,
+            pub status: PostStatus,
+            pub word_count: i32,
             pub categories: Vec<Category>,
+            pub tags: Vec<Tag>,
+        }
+
+        /// Delimiters `ts_headline` puts around matches; control characters
+        /// so they cannot clash with the escaping done in `into_result`.
+        pub const HIGHLIGHT_START: &str = "\u{2}";
+        pub const HIGHLIGHT_STOP: &str = "\u{3}";
+
+        #[derive(sqlx::FromRow)]
+        pub struct SqlSearchResult {
+            pub id: i64,
+            pub title: String,
+            pub description: String,
+            pub hero_image: String,
+            pub published_at: DateTime<Local>,
+            pub slug: String,
+            pub status: PostStatus,
+            pub word_count: i32,
+            pub categories: Vec<Category>,
+            pub tags: Vec<Tag>,
+            pub snippet: String,
+        }
+
+        impl SqlSearchResult {
+            pub fn into_result(self) -> SearchResult {
+                let snippet = self.snippet
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+                    .replace(HIGHLIGHT_START, "<mark>")
+                    .replace(HIGHLIGHT_STOP, "</mark>");
+
+                SearchResult {
+                    post: SqlPostSummary {
+                        id: self.id,
+                        title: self.title,
+                        description: self.description,
+                        hero_image: self.hero_image,
+                        published_at: self.published_at,
+                        slug: self.slug,
+                        status: self.status,
+                        word_count: self.word_count,
+                        categories: self.categories,
+                        tags: self.tags,
+                    }.into_summary(),
+                    snippet,
+                }
+            }
+        }
+
+        /// A listed post or category slug with the last time its page
+        /// changed, as listed in the sitemap.
+        #[derive(Debug, Clone)]
+        pub struct SitemapEntry {
+            pub slug: String,
+            pub lastmod: Option<DateTime<Utc>>,
+        }
+
+        /// Fields written by `PostRepository::create_post` and `update_post`.
+        #[derive(Debug, Clone)]
+        pub struct PostInput {
+            pub title: String,
+            pub description: String,
+            pub hero_image: String,
+            pub content: String,
+            pub published_at: DateTime<Local>,
+            pub slug: String,
+            pub status: PostStatus,
+            pub category_ids: Vec<i64>,
+            pub tag_names: Vec<String>,
+            pub series_title: Option<String>,
+            pub series_position: Option<i32>,
+        }
+
+        impl RenderedMarkdown {
+            pub fn render(markdown: &str) -> Result<Self, RenderError> {
+                let HTMLOutput{content, toc,..} = femark::process_markdown_to_html(markdown)
+                    .map_err(|e| RenderError(format!("{e:#}")))?;
+                Ok(Self { content, toc })
+            }
+        }
+
+        /// Whether a tag name or series title has a slug, and both it and
+        /// the slug fit in `max_length` characters.
+        fn is_valid_name(name: &str, max_length: usize) -> bool {
+            let slug = Tag::slugify(name);
+            !slug.is_empty() && slug.len() <= max_length && name.chars().count() <= max_length
+        }
+
+        impl PostForm {
+            /// Validates the form into repository input, interpreting
+            /// `published_at` in the server's local time zone.
+            pub fn into_input(self) -> Result<PostInput, String> {
+                let published_at = NaiveDateTime::parse_from_str(&self.published_at, FORM_DATETIME_FORMAT)
+                    .ok()
+                    .and_then(|date| date.and_local_timezone(Local).single())
+                    .ok_or_else(|| format!("invalid publish date: {}", self.published_at))?;
+
+                if self.title.trim().is_empty() {
+                    return Err("title is required".to_string());
+                }
+                if self.slug.is_empty() || !self.slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+                    return Err("slug must only contain lowercase letters, digits and dashes".to_string());
+                }
+                if let Some(tag) = self.tags.iter().find(|tag| !is_valid_name(tag, MAX_TAG_LENGTH)) {
+                    return Err(format!("invalid tag `{tag}`: tags need a letter or digit and at most {MAX_TAG_LENGTH} characters, counting `+` and `#` as `plus` and `sharp`"));
+                }
+                let series_title = Some(self.series.trim().to_string()).filter(|title| !title.is_empty());
+                if let Some(title) = &series_title {
+                    if !is_valid_name(title, MAX_SERIES_TITLE_LENGTH) {
+                        return Err(format!("invalid series `{title}`: series titles need a letter or digit and at most {MAX_SERIES_TITLE_LENGTH} characters, counting `+` and `#` as `plus` and `sharp`"));
+                    }
+                }
+                if self.series_position.is_some_and(|position| position < 1) {
+                    return Err("series part numbers start at 1".to_string());
+                }
+
+                Ok(PostInput {
+                    title: self.title,
+                    description: self.description,
+                    hero_image: self.hero_image,
+                    content: self.content,
+                    published_at,
+                    slug: self.slug,
+                    status: self.status,
+                    category_ids: self.category_ids,
+                    tag_names: self.tags,
+                    series_position: series_title.as_ref().and(self.series_position),
+                    series_title,
+                })
+            }
+        }
+
+        /// Keyset position in any `PostSort` ordering: by `word_count` when
+        /// sorting by length, then `published_at DESC, id DESC`.
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub struct PostCursor {
+            pub published_at: DateTime<Local>,
+            pub id: i64,
+            pub word_count: i32,
+        }
+
+        impl PostCursor {
+            pub fn encode(&self) -> String {
+                format!("{:x}-{:x}-{:x}", self.published_at.timestamp_micros() as u64, self.id, self.word_count)
+            }
+
+            pub fn decode(cursor: &str) -> Option<Self> {
+                let mut parts = cursor.split('-');
+                let micros = u64::from_str_radix(parts.next()?, 16).ok()? as i64;
+                let published_at = DateTime::from_timestamp_micros(micros)?.with_timezone(&Local);
+                let id = i64::from_str_radix(parts.next()?, 16).ok()?;
+                let word_count = i32::from_str_radix(parts.next()?, 16).ok()?;
+                if parts.next().is_some() {
+                    return None;
+                }
+                Some(Self { published_at, id, word_count })
+            }
+        }
+
+        impl SqlPostSummary {
+            pub fn cursor(&self) -> PostCursor {
+                PostCursor {
+                    published_at: self.published_at,
+                    id: self.id,
+                    word_count: self.word_count,
+                }
+            }
+
+            pub fn into_summary(self) -> PostSummary {
+                PostSummary {
+                    id: self.id,
+                    title: self.title,
+                    description: self.description,
+                    hero_image: self.hero_image,
+                    published_at: self.published_at.format("%d/%m/%Y").to_string(),
+                    slug: self.slug,
+                    status: self.status,
+                    word_count: self.word_count,
+                    reading_time_minutes: reading_time_minutes(self.word_count),
+                    categories: self.categories,
+                    tags: self.tags,
+                }
+            }
         }
 
         impl SqlPost {
-            pub fn into_post(self) -> BlogPost {
-                let HTMLOutput{content, toc,..} = femark::process_markdown_to_html(&self.content).unwrap_or_default();
+            /// The cached render of the post's content, unless an older
+            /// renderer produced it.
+            pub fn take_cached_render(&mut self) -> Option<RenderedMarkdown> {
+                if self.rendered_version != Some(RENDERER_VERSION) {
+                    return None;
+                }
+                Some(RenderedMarkdown {
+                    content: self.rendered_content.take()?,
+                    toc: self.rendered_toc.take(),
+                })
+            }
+
+            pub fn into_post(self, rendered: RenderedMarkdown) -> BlogPost {
+                let RenderedMarkdown{content, toc} = rendered;
                 BlogPost {
                     id: self.id,
                     title: self.title,
                     description: self.description,
                     hero_image: self.hero_image,
                     published_at: self.published_at.format("%d/%m/%Y").to_string(),
+                    published_at_rfc3339: self.published_at.to_rfc3339(),
+                    updated_at_rfc3339: self.updated_at.to_rfc3339(),
+                    word_count: self.word_count,
+                    reading_time_minutes: reading_time_minutes(self.word_count),
                     content,
                     toc,
                     slug: self.slug,
                     categories: self.categories,
+                    tags: self.tags,
+                    series: None,
+                    previous: None,
+                    next: None,
+                    related: Vec::new(),
+                    site_url: String::new(),
                 }
             }
         }
     }
 }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use chrono::{DateTime, Local};
+    use super::{count_words, PostCursor};
+
+    fn cursor() -> PostCursor {
+        PostCursor {
+            published_at: DateTime::from_timestamp_micros(1_704_067_200_123_456).unwrap().with_timezone(&Local),
+            id: 42,
+            word_count: 1234,
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = cursor();
+        assert_eq!(PostCursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn cursor_rejects_malformed_input() {
+        let encoded = cursor().encode();
+        for malformed in [
+            "",
+            "-",
+            "not-a-cursor",
+            "60d6b1a8b4f40-2a",
+            &format!("{encoded}-1"),
+            &format!("{encoded}z"),
+            &encoded.replace('-', "--"),
+        ] {
+            assert_eq!(PostCursor::decode(malformed), None, "{malformed:?}");
+        }
+    }
+
+    #[test]
+    fn count_words_counts_prose() {
+        assert_eq!(count_words(""), 0);
+        assert_eq!(count_words("# A title\n\nTwo   words.\n\n- one\n- two"), 6);
+    }
+
+    #[test]
+    fn count_words_skips_code_blocks_images_and_html() {
+        let markdown = "Some text\n\n```rust\nfn main() {}\n```\n\n    indented code\n\n![alt text](/image.png)\n\n<div>raw html</div>\n";
+        assert_eq!(count_words(markdown), 2);
+    }
+
+    #[test]
+    fn count_words_keeps_words_split_by_inline_markup() {
+        assert_eq!(count_words("un**believ**able `inline code` [link text](/a)"), 5);
+        assert_eq!(count_words("line one\nline two  \nthree"), 5);
+    }
+}
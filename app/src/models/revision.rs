@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+use cfg_if::cfg_if;
+
+/// A snapshot of a post's title, description and markdown taken right
+/// before it was overwritten.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PostRevision {
+    pub id: i64,
+    pub title: String,
+    pub description: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DiffTag {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    pub text: String,
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use similar::{ChangeTag, TextDiff};
+
+        /// Line-level diff turning `old` into `new`.
+        pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+            TextDiff::from_lines(old, new)
+                .iter_all_changes()
+                .map(|change| DiffLine {
+                    tag: match change.tag() {
+                        ChangeTag::Equal => DiffTag::Equal,
+                        ChangeTag::Insert => DiffTag::Insert,
+                        ChangeTag::Delete => DiffTag::Delete,
+                    },
+                    text: change.value().trim_end_matches('\n').to_string(),
+                })
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use std::cmp::Reverse;
+
+use serde::{Serialize, Deserialize};
+use cfg_if::cfg_if;
+
+/// Path the prebuilt index is served from.
+pub const SEARCH_INDEX_PATH: &str = "/search-index.bin";
+
+/// Query words of at least this many characters tolerate one typo, and twice
+/// as long ones two.
+const FUZZY_MIN_LEN: usize = 4;
+
+/// A post as listed in the instant search results.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexedPost {
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+}
+
+/// An indexed word and the posts containing it, as `(post, weight)` pairs
+/// where `post` indexes `SearchIndex::posts`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexTerm {
+    pub term: String,
+    pub postings: Vec<(u32, u8)>,
+}
+
+/// Compact word index over the titles, descriptions, headings, categories and
+/// tags of every listed post, built by the server and searched in the browser.
+/// `terms` is sorted so prefix lookups are a binary search.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SearchIndex {
+    pub posts: Vec<IndexedPost>,
+    pub terms: Vec<IndexTerm>,
+}
+
+impl SearchIndex {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
+    /// Posts matching every word of `query`, best first. A word matches the
+    /// indexed words it is a prefix of; when it has none it falls back to
+    /// prefixes within one or two typos.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&IndexedPost> {
+        let mut totals: Option<Vec<u32>> = None;
+
+        for word in tokenize(query) {
+            let scores = self.score_prefix(&word).or_else(|| self.score_fuzzy(&word));
+            let Some(scores) = scores else {
+                return Vec::new();
+            };
+
+            totals = Some(match totals {
+                None => scores,
+                Some(totals) => totals
+                    .into_iter()
+                    .zip(scores)
+                    .map(|(total, score)| if total == 0 || score == 0 { 0 } else { total + score })
+                    .collect(),
+            });
+        }
+
+        let Some(totals) = totals else {
+            return Vec::new();
+        };
+
+        let mut hits = totals
+            .into_iter()
+            .enumerate()
+            .filter(|(_, score)| *score > 0)
+            .collect::<Vec<_>>();
+        // Stable, so equally good hits keep the newest-first order of `posts`.
+        hits.sort_by_key(|(_, score)| Reverse(*score));
+
+        hits.into_iter()
+            .take(limit)
+            .map(|(post, _)| &self.posts[post])
+            .collect()
+    }
+
+    fn score_prefix(&self, word: &str) -> Option<Vec<u32>> {
+        let start = self.terms.partition_point(|term| term.term.as_str() < word);
+        let matches = self.terms[start..]
+            .iter()
+            .take_while(|term| term.term.starts_with(word))
+            // Whole words rank above words merely starting with the query.
+            .map(|term| (term, if term.term == word { 2 } else { 1 }));
+
+        self.score(matches)
+    }
+
+    fn score_fuzzy(&self, word: &str) -> Option<Vec<u32>> {
+        let len = word.chars().count();
+        if len < FUZZY_MIN_LEN {
+            return None;
+        }
+        let max_typos = if len >= FUZZY_MIN_LEN * 2 { 2 } else { 1 };
+
+        let matches = self.terms
+            .iter()
+            .filter(|term| prefix_distance(word, &term.term) <= max_typos)
+            .map(|term| (term, 1));
+
+        self.score(matches)
+    }
+
+    /// Best weight per post over the matched terms, `None` if nothing matched.
+    fn score<'a>(&self, matches: impl Iterator<Item = (&'a IndexTerm, u32)>) -> Option<Vec<u32>> {
+        let mut scores = vec![0; self.posts.len()];
+        let mut matched = false;
+
+        for (term, boost) in matches {
+            for &(post, weight) in &term.postings {
+                let score = &mut scores[post as usize];
+                *score = (*score).max(u32::from(weight) * boost);
+                matched = true;
+            }
+        }
+
+        matched.then_some(scores)
+    }
+}
+
+/// Lowercased alphanumeric words of `text`.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Smallest edit distance between `word` and any prefix of `term`.
+fn prefix_distance(word: &str, term: &str) -> usize {
+    let term = term.chars().collect::<Vec<_>>();
+    // `row[j]`: distance between the word read so far and `term[..j]`.
+    let mut row = (0..=term.len()).collect::<Vec<_>>();
+
+    for (i, w) in word.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, t) in term.iter().enumerate() {
+            let substitution = previous + usize::from(w != *t);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+
+    row.into_iter().min().unwrap_or_default()
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::collections::BTreeMap;
+        use std::future::Future;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Mutex, MutexGuard};
+        use std::time::{Duration, Instant};
+        use axum::body::Bytes;
+        use crate::models::{category::Category, tag::Tag};
+
+        const TITLE_WEIGHT: u8 = 8;
+        const HEADING_WEIGHT: u8 = 4;
+        const CATEGORY_WEIGHT: u8 = 4;
+        const TAG_WEIGHT: u8 = 4;
+        const DESCRIPTION_WEIGHT: u8 = 2;
+
+        /// The parts of a listed post the search index is built from;
+        /// `content` is the raw markdown, only read for its headings.
+        #[derive(sqlx::FromRow)]
+        pub struct IndexSource {
+            pub slug: String,
+            pub title: String,
+            pub description: String,
+            pub content: String,
+            pub categories: Vec<Category>,
+            pub tags: Vec<Tag>,
+        }
+
+        impl SearchIndex {
+            pub fn build(sources: Vec<IndexSource>) -> Self {
+                let mut terms: BTreeMap<String, BTreeMap<u32, u8>> = BTreeMap::new();
+                let mut posts = Vec::with_capacity(sources.len());
+
+                for (post, source) in sources.into_iter().enumerate() {
+                    let post = post as u32;
+                    let mut add = |text: &str, weight: u8| {
+                        for word in tokenize(text) {
+                            let entry = terms.entry(word).or_default().entry(post).or_default();
+                            *entry = entry.saturating_add(weight);
+                        }
+                    };
+
+                    add(&source.title, TITLE_WEIGHT);
+                    add(&source.description, DESCRIPTION_WEIGHT);
+                    for heading in markdown_headings(&source.content) {
+                        add(heading, HEADING_WEIGHT);
+                    }
+                    for category in &source.categories {
+                        add(&category.name, CATEGORY_WEIGHT);
+                    }
+                    for tag in &source.tags {
+                        add(&tag.name, TAG_WEIGHT);
+                    }
+
+                    posts.push(IndexedPost {
+                        slug: source.slug,
+                        title: source.title,
+                        description: source.description,
+                    });
+                }
+
+                SearchIndex {
+                    posts,
+                    terms: terms
+                        .into_iter()
+                        .map(|(term, postings)| IndexTerm { term, postings: postings.into_iter().collect() })
+                        .collect(),
+                }
+            }
+
+            pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+                bincode::serialize(self)
+            }
+        }
+
+        /// ATX headings (`# Heading`) of a markdown document, skipping
+        /// fenced code blocks where `#` usually starts a comment.
+        fn markdown_headings(markdown: &str) -> impl Iterator<Item = &str> {
+            let mut in_code_block = false;
+            markdown.lines().filter_map(move |line| {
+                let line = line.trim_start();
+                if line.starts_with("```") || line.starts_with("~~~") {
+                    in_code_block = !in_code_block;
+                    return None;
+                }
+                if in_code_block {
+                    return None;
+                }
+
+                let text = line.trim_start_matches('#');
+                let level = line.len() - text.len();
+                ((1..=6).contains(&level) && (text.is_empty() || text.starts_with(' ')))
+                    .then(|| text.trim().trim_end_matches('#').trim_end())
+            })
+        }
+
+        /// How long a built index is served before it is rebuilt, so
+        /// scheduled posts show up soon after they go live.
+        const MAX_AGE: Duration = Duration::from_secs(60);
+
+        /// The encoded index, with the `ETag` it is served under.
+        #[derive(Debug, Clone)]
+        pub struct EncodedSearchIndex {
+            pub bytes: Bytes,
+            pub etag: String,
+        }
+
+        /// The last built index, kept for `MAX_AGE` or until it is cleared
+        /// because a post was written.
+        #[derive(Debug, Clone, Default)]
+        pub struct SearchIndexCache {
+            entry: Arc<Mutex<Option<(Instant, EncodedSearchIndex)>>>,
+            /// Bumped by every `clear`.
+            generation: Arc<AtomicU64>,
+        }
+
+        impl SearchIndexCache {
+            /// The cached index, or the one `build` returns, which is cached
+            /// unless it is an error or the cache was cleared meanwhile.
+            pub async fn get_or_try_insert_with<E, F, Fut>(&self, build: F) -> Result<EncodedSearchIndex, E>
+            where
+                F: FnOnce() -> Fut,
+                Fut: Future<Output = Result<EncodedSearchIndex, E>>,
+            {
+                let cached = self.entry()
+                    .as_ref()
+                    .filter(|(built_at, _)| built_at.elapsed() < MAX_AGE)
+                    .map(|(_, index)| index.clone());
+                if let Some(index) = cached {
+                    return Ok(index);
+                }
+
+                let generation = self.generation.load(Ordering::Acquire);
+                let index = build().await?;
+                let mut entry = self.entry();
+                if self.generation.load(Ordering::Acquire) == generation {
+                    *entry = Some((Instant::now(), index.clone()));
+                }
+                Ok(index)
+            }
+
+            pub fn clear(&self) {
+                let mut entry = self.entry();
+                *entry = None;
+                self.generation.fetch_add(1, Ordering::Release);
+            }
+
+            fn entry(&self) -> MutexGuard<'_, Option<(Instant, EncodedSearchIndex)>> {
+                // A panic while holding the lock cannot leave the entry half-written.
+                self.entry.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{prefix_distance, tokenize, IndexTerm, IndexedPost, SearchIndex};
+
+    /// An index of posts with the given titles, newest first, each word of
+    /// a title weighted as given.
+    fn index(posts: &[(&str, u8)]) -> SearchIndex {
+        let mut terms: BTreeMap<String, Vec<(u32, u8)>> = BTreeMap::new();
+        for (post, (title, weight)) in posts.iter().enumerate() {
+            for word in tokenize(title) {
+                terms.entry(word).or_default().push((post as u32, *weight));
+            }
+        }
+
+        SearchIndex {
+            posts: posts
+                .iter()
+                .map(|(title, _)| IndexedPost {
+                    slug: title.to_lowercase(),
+                    title: title.to_string(),
+                    description: String::new(),
+                })
+                .collect(),
+            terms: terms.into_iter().map(|(term, postings)| IndexTerm { term, postings }).collect(),
+        }
+    }
+
+    fn search<'a>(index: &'a SearchIndex, query: &str) -> Vec<&'a str> {
+        index.search(query, 10).into_iter().map(|post| post.title.as_str()).collect()
+    }
+
+    #[test]
+    fn search_ranks_whole_words_above_prefixes() {
+        let index = index(&[("Rustacean Life", 1), ("Rust Tips", 1)]);
+        assert_eq!(search(&index, "rust"), ["Rust Tips", "Rustacean Life"]);
+        assert_eq!(search(&index, "rusta"), ["Rustacean Life"]);
+    }
+
+    #[test]
+    fn search_ranks_by_weight_then_newest_first() {
+        let index = index(&[("Async Rust", 1), ("Rust Macros", 4), ("Rust Errors", 1)]);
+        assert_eq!(search(&index, "rust"), ["Rust Macros", "Async Rust", "Rust Errors"]);
+        assert_eq!(index.search("rust", 2).len(), 2);
+    }
+
+    #[test]
+    fn search_needs_every_word_to_match() {
+        let index = index(&[("Async Rust", 1), ("Rust Macros", 1)]);
+        assert_eq!(search(&index, "macros RUST"), ["Rust Macros"]);
+        assert!(search(&index, "rust python").is_empty());
+    }
+
+    #[test]
+    fn search_finds_nothing_for_an_empty_query() {
+        let index = index(&[("Async Rust", 1)]);
+        assert!(search(&index, "").is_empty());
+        assert!(search(&index, " -- ").is_empty());
+        assert!(search(&SearchIndex::default(), "rust").is_empty());
+    }
+
+    #[test]
+    fn search_allows_more_typos_in_longer_words() {
+        let index = index(&[("PostgreSQL Tuning", 1), ("Cats", 1)]);
+        // Four to seven characters: one typo.
+        assert_eq!(search(&index, "tuxing"), ["PostgreSQL Tuning"]);
+        assert!(search(&index, "tbxing").is_empty());
+        assert_eq!(search(&index, "cbts"), ["Cats"]);
+        // Eight or more: two.
+        assert_eq!(search(&index, "postgers"), ["PostgreSQL Tuning"]);
+        assert!(search(&index, "pistgers").is_empty());
+        // Under four: none.
+        assert!(search(&index, "cts").is_empty());
+    }
+
+    #[test]
+    fn search_matches_non_ascii_titles() {
+        let index = index(&[("Café Crème", 1), ("日本語 入門", 1), ("Straße", 1)]);
+        assert_eq!(search(&index, "CAFÉ"), ["Café Crème"]);
+        assert_eq!(search(&index, "caf"), ["Café Crème"]);
+        assert_eq!(search(&index, "creme"), ["Café Crème"]);
+        assert_eq!(search(&index, "日本語"), ["日本語 入門"]);
+        assert_eq!(search(&index, "straß"), ["Straße"]);
+    }
+
+    #[test]
+    fn prefix_distance_is_to_the_closest_prefix() {
+        assert_eq!(prefix_distance("rust", "rustacean"), 0);
+        assert_eq!(prefix_distance("rost", "rustacean"), 1);
+        assert_eq!(prefix_distance("rsut", "rust"), 2);
+        assert_eq!(prefix_distance("rusty", "rust"), 1);
+        assert_eq!(prefix_distance("crème", "creme"), 1);
+        assert_eq!(prefix_distance("", "rust"), 0);
+        assert_eq!(prefix_distance("rust", ""), 4);
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn markdown_headings_skips_code_blocks() {
+        let markdown = "# Title\ntext\n## Setup ##\n```sh\n# a comment\n```\n  ### Indented\n~~~\n## Fenced\n~~~\n#hashtag\n####### Seven\n";
+        assert_eq!(super::markdown_headings(markdown).collect::<Vec<_>>(), ["Title", "Setup", "Indented"]);
+    }
+}
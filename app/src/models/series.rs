@@ -0,0 +1,48 @@
+use serde::{Serialize, Deserialize};
+
+/// Longest series title, matching the `series.title` column.
+pub const MAX_SERIES_TITLE_LENGTH: usize = 255;
+
+/// An ordered run of posts, such as a tutorial written over several parts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(sqlx::FromRow))]
+pub struct Series {
+    pub id: i64,
+    pub title: String,
+    pub slug: String,
+    pub description: Option<String>,
+}
+
+/// A post of a series, as linked from the other parts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(sqlx::FromRow))]
+pub struct SeriesPart {
+    pub id: i64,
+    pub title: String,
+    pub slug: String,
+    /// The part's number within the series.
+    pub position: i32,
+}
+
+impl SeriesPart {
+    pub fn href(&self) -> String {
+        format!("/blog/{}", self.slug)
+    }
+}
+
+/// A series with its parts in reading order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeriesWithParts {
+    pub series: Series,
+    pub parts: Vec<SeriesPart>,
+    /// Number of the series' last part, counting parts not listed yet, so
+    /// that unpublished parts do not shift the numbering.
+    pub last_position: i32,
+}
+
+impl SeriesWithParts {
+    /// Index of the post among `parts`, if it is one of them.
+    pub fn index_of(&self, post_id: i64) -> Option<usize> {
+        self.parts.iter().position(|part| part.id == post_id)
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Serialize, Deserialize};
+
+/// Longest tag name, matching the `tags.name` column.
+pub const MAX_TAG_LENGTH: usize = 100;
+
+/// A free-form label; unlike categories, tags are created simply by
+/// attaching them to a post.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(sqlx::FromRow, sqlx::Type))]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+}
+
+impl Tag {
+    /// The listing of posts with this tag.
+    pub fn href(&self) -> String {
+        format!("/blog/tag/{}", self.slug)
+    }
+
+    /// Slug of a tag name: its lowercased ASCII letters and digits, with
+    /// single dashes between words. `+` and `#` are spelled out as words, so
+    /// "C", "C++" and "C#" each get their own slug. Empty if the name has
+    /// none of these.
+    pub fn slugify(name: &str) -> String {
+        let mut spelled = String::with_capacity(name.len());
+        for c in name.chars() {
+            match c {
+                '+' => spelled.push_str(" plus "),
+                '#' => spelled.push_str(" sharp "),
+                c => spelled.push(c),
+            }
+        }
+
+        spelled
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+}
+
+/// Tag names from a comma-separated list, trimmed, without empty entries or
+/// names sharing a slug with an earlier one.
+pub fn parse_tag_names(input: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in input.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        if !names.iter().any(|n| Tag::slugify(n) == Tag::slugify(name)) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_tag_names, Tag};
+
+    #[test]
+    fn slugify_spells_out_plus_and_sharp() {
+        assert_eq!(Tag::slugify("C"), "c");
+        assert_eq!(Tag::slugify("C++"), "c-plus-plus");
+        assert_eq!(Tag::slugify("C#"), "c-sharp");
+        assert_eq!(Tag::slugify("  Web -- Dev! "), "web-dev");
+        assert_eq!(Tag::slugify("日本"), "");
+    }
+
+    #[test]
+    fn parse_tag_names_keeps_symbol_variants_apart() {
+        assert_eq!(parse_tag_names("C, C++, c, C#,, c++"), ["C", "C++", "C#"]);
+    }
+}
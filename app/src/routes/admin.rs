@@ -0,0 +1,480 @@
+use leptos::prelude::*;
+use leptos::either::*;
+use leptos_router::components::{Form, Outlet, Redirect, A};
+use leptos_router::hooks::{use_params_map, use_query_map};
+
+use crate::models::{
+    cache::CacheStats,
+    post::{PostForm, PostSummary, RenderedMarkdown},
+    revision::{DiffLine, DiffTag, PostRevision},
+    user::AdminUser,
+};
+use crate::components::post_editor::PostEditor;
+use super::blog_list::get_categories;
+
+#[server(Login)]
+pub async fn login(username: String, password: String) -> Result<(), ServerFnError> {
+    match crate::auth::login(&username, &password).await? {
+        Some(_) => {
+            leptos_axum::redirect("/admin");
+            Ok(())
+        }
+        None => Err(ServerFnError::ServerError("invalid username or password".to_string())),
+    }
+}
+
+#[server(Logout)]
+pub async fn logout() -> Result<(), ServerFnError> {
+    crate::auth::logout().await?;
+    leptos_axum::redirect("/admin/login");
+    Ok(())
+}
+
+#[server(GetCurrentAdmin)]
+pub async fn get_current_admin() -> Result<Option<AdminUser>, ServerFnError> {
+    crate::auth::current_admin().await
+}
+
+#[server(GetAdminPosts)]
+pub async fn get_admin_posts() -> Result<Vec<PostSummary>, ServerFnError> {
+    use crate::state::AppState;
+
+    crate::auth::require_admin().await?;
+    let state = expect_context::<AppState>();
+
+    state.db.get_all_post_summaries()
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[server(GetPostForm)]
+pub async fn get_post_form(id: Option<i64>) -> Result<PostForm, ServerFnError> {
+    use crate::state::AppState;
+    use crate::models::post::FORM_DATETIME_FORMAT;
+
+    crate::auth::require_admin().await?;
+    let state = expect_context::<AppState>();
+
+    match id {
+        Some(id) => state.db.get_post_form(id)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string())),
+        None => Ok(PostForm {
+            published_at: chrono::Local::now().format(FORM_DATETIME_FORMAT).to_string(),
+            ..Default::default()
+        }),
+    }
+}
+
+#[server(SavePost)]
+pub async fn save_post(post: PostForm) -> Result<i64, ServerFnError> {
+    use crate::state::AppState;
+
+    crate::auth::require_admin().await?;
+    let state = expect_context::<AppState>();
+
+    let id = post.id;
+    let input = match post.into_input() {
+        Ok(input) => input,
+        Err(e) => return Err(ServerFnError::Args(e)),
+    };
+
+    let id = match id {
+        Some(id) => state.db.update_post(id, &input).await?,
+        None => state.db.create_post(&input).await?,
+    };
+    state.search_index.clear();
+    state.cache.invalidate();
+
+    leptos_axum::redirect("/admin");
+    Ok(id)
+}
+
+/// Renders markdown exactly as the post page would, for the editor preview.
+#[server(PreviewMarkdown)]
+pub async fn preview_markdown(markdown: String) -> Result<RenderedMarkdown, ServerFnError> {
+    crate::auth::require_admin().await?;
+
+    RenderedMarkdown::render(&markdown).map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[server(DeletePost)]
+pub async fn delete_post(id: i64) -> Result<(), ServerFnError> {
+    use crate::state::AppState;
+
+    crate::auth::require_admin().await?;
+    let state = expect_context::<AppState>();
+
+    state.db.delete_post(id).await?;
+    state.search_index.clear();
+    state.cache.invalidate();
+
+    Ok(())
+}
+
+/// Hits and misses of the read caches behind the public pages.
+#[server(GetCacheStats)]
+pub async fn get_cache_stats() -> Result<Vec<CacheStats>, ServerFnError> {
+    use crate::state::AppState;
+
+    crate::auth::require_admin().await?;
+
+    Ok(expect_context::<AppState>().cache.stats())
+}
+
+#[server(GetRevisions)]
+pub async fn get_revisions(post_id: i64) -> Result<Vec<PostRevision>, ServerFnError> {
+    use crate::state::AppState;
+
+    crate::auth::require_admin().await?;
+    let state = expect_context::<AppState>();
+
+    state.db.get_revisions(post_id)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Diffs the markdown of two revisions of a post. `from` defaults to the
+/// latest revision and `to` to the post's current content; `None` is
+/// returned when the post has no revisions to compare.
+#[server(DiffRevisions)]
+pub async fn diff_revisions(
+    post_id: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Option<Vec<DiffLine>>, ServerFnError> {
+    use crate::state::AppState;
+    use crate::models::revision::diff_lines;
+
+    crate::auth::require_admin().await?;
+    let state = expect_context::<AppState>();
+
+    let revisions = state.db.get_revisions(post_id).await?;
+    let current = state.db.get_post_form(post_id).await?.content;
+
+    let Some(from) = from.or_else(|| revisions.first().map(|r| r.id)) else {
+        return Ok(None);
+    };
+    let content = |revision: Option<i64>| match revision {
+        None => Some(current.as_str()),
+        Some(id) => revisions.iter().find(|r| r.id == id).map(|r| r.content.as_str()),
+    };
+
+    match (content(Some(from)), content(to)) {
+        (Some(old), Some(new)) => Ok(Some(diff_lines(old, new))),
+        _ => Err(ServerFnError::Args("revision not found".to_string())),
+    }
+}
+
+#[server(RestoreRevision)]
+pub async fn restore_revision(post_id: i64, revision_id: i64) -> Result<(), ServerFnError> {
+    use crate::state::AppState;
+
+    crate::auth::require_admin().await?;
+    let state = expect_context::<AppState>();
+
+    state.db.restore_revision(post_id, revision_id).await?;
+    state.search_index.clear();
+    state.cache.invalidate();
+
+    leptos_axum::redirect(&format!("/admin/posts/{post_id}"));
+    Ok(())
+}
+
+#[component]
+pub fn AdminLayout() -> impl IntoView {
+    let admin = Resource::new(|| (), |_| get_current_admin());
+    let logout = ServerAction::<Logout>::new();
+
+    view! {
+        <div class="max-w-5xl mx-auto py-12 px-4 sm:px-6 lg:px-8 mb-8">
+            <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+                {move || match admin.get() {
+                    None => EitherOf4::A(view! { <div>"Loading..."</div> }),
+                    Some(Ok(Some(user))) => EitherOf4::B(view! {
+                        <div class="flex justify-between items-center mb-8">
+                            <A href="/admin">
+                                <h1 class="text-3xl font-bold">"Admin"</h1>
+                            </A>
+                            <ActionForm action=logout>
+                                <span class="text-sm mr-4">"Signed in as " {user.username}</span>
+                                <button type="submit" class="btn btn-sm">"Log out"</button>
+                            </ActionForm>
+                        </div>
+                        <Outlet/>
+                    }),
+                    Some(Ok(None)) => EitherOf4::C(view! { <Redirect path="/admin/login"/> }),
+                    Some(Err(e)) => EitherOf4::D(view! {
+                        <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                            "Error checking session: " {e.to_string()}
+                        </div>
+                    }),
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+pub fn AdminLogin() -> impl IntoView {
+    let login = ServerAction::<Login>::new();
+
+    view! {
+        <div class="min-h-screen flex items-center justify-center p-4">
+            <div class="card w-96 bg-base-100 shadow-xl">
+                <div class="card-body">
+                    <h1 class="card-title text-2xl font-bold mb-4">"Sign in"</h1>
+                    <ActionForm action=login>
+                        <div class="flex flex-col gap-4">
+                            <input
+                                type="text"
+                                name="username"
+                                placeholder="Username"
+                                autocomplete="username"
+                                class="input input-bordered"
+                                required
+                            />
+                            <input
+                                type="password"
+                                name="password"
+                                placeholder="Password"
+                                autocomplete="current-password"
+                                class="input input-bordered"
+                                required
+                            />
+                            <button type="submit" class="btn btn-accent">"Sign in"</button>
+                        </div>
+                    </ActionForm>
+                    {move || login.value().get().and_then(Result::err).map(|e| view! {
+                        <div class="text-red-500 mt-4">{e.to_string()}</div>
+                    })}
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn AdminPostList() -> impl IntoView {
+    let delete = ServerAction::<DeletePost>::new();
+    let posts = Resource::new(move || delete.version().get(), |_| get_admin_posts());
+    let cache_stats = Resource::new(move || delete.version().get(), |_| get_cache_stats());
+
+    view! {
+        <div class="flex justify-between items-center mb-4">
+            <h2 class="text-2xl font-semibold">"Posts"</h2>
+            <A href="/admin/posts/new">
+                <div class="btn btn-sm btn-accent">"New post"</div>
+            </A>
+        </div>
+        <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+            {move || match posts.get() {
+                None => EitherOf3::A(view! { <div>"Loading..."</div> }),
+                Some(Ok(posts)) => EitherOf3::B(view! {
+                    <table class="table w-full">
+                        <thead>
+                            <tr>
+                                <th>"Title"</th>
+                                <th>"Slug"</th>
+                                <th>"Status"</th>
+                                <th>"Published"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {posts.into_iter().map(|post| {
+                                let id = post.id;
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <A href=format!("/admin/posts/{id}")>{post.title}</A>
+                                        </td>
+                                        <td class="text-sm">{post.slug}</td>
+                                        <td class="text-sm">{post.status.as_str()}</td>
+                                        <td class="text-sm">{post.published_at}</td>
+                                        <td class="text-right">
+                                            <button
+                                                class="btn btn-xs btn-error"
+                                                disabled=move || delete.pending().get()
+                                                on:click=move |_| {
+                                                    delete.dispatch(DeletePost { id });
+                                                }
+                                            >
+                                                "Delete"
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                }),
+                Some(Err(e)) => EitherOf3::C(view! {
+                    <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                        "Error loading posts: " {e.to_string()}
+                    </div>
+                }),
+            }}
+        </Suspense>
+        <Suspense>
+            {move || cache_stats.get().and_then(Result::ok).map(|stats| view! {
+                <div class="flex flex-wrap gap-4 mt-8 text-sm">
+                    {stats.into_iter().map(|stats| view! {
+                        <span>
+                            {format!("Cache {}: {} hits, {} misses, {} entries", stats.name, stats.hits, stats.misses, stats.entries)}
+                        </span>
+                    }).collect::<Vec<_>>()}
+                </div>
+            })}
+        </Suspense>
+    }
+}
+
+#[component]
+pub fn AdminPostEditor() -> impl IntoView {
+    let params = use_params_map();
+    let id = move || params.with(|params| params.get("id")).and_then(|id| id.parse::<i64>().ok());
+    let post = Resource::new(id, get_post_form);
+    let categories = Resource::new(|| (), |_| get_categories());
+
+    view! {
+        <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+            {move || match (post.get(), categories.get()) {
+                (Some(Ok(post)), Some(Ok(categories))) => EitherOf3::A({
+                    let categories = categories.into_iter().map(|count| count.category).collect::<Vec<_>>();
+                    view! { <PostEditor post categories/> }
+                }),
+                (Some(Err(e)), _) | (_, Some(Err(e))) => EitherOf3::B(view! {
+                    <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                        "Error loading post: " {e.to_string()}
+                    </div>
+                }),
+                _ => EitherOf3::C(view! { <div>"Loading..."</div> }),
+            }}
+        </Suspense>
+    }
+}
+
+#[component]
+pub fn AdminPostRevisions() -> impl IntoView {
+    let params = use_params_map();
+    let query = use_query_map();
+    let post_id = move || params.with(|params| params.get("id")).and_then(|id| id.parse::<i64>().ok()).unwrap_or_default();
+    let revision_param = move |key: &str| query.with(|query| query.get(key)).and_then(|id| id.parse::<i64>().ok());
+
+    let restore = ServerAction::<RestoreRevision>::new();
+    let revisions = Resource::new(post_id, get_revisions);
+    let diff = Resource::new(
+        move || (post_id(), revision_param("from"), revision_param("to")),
+        |(post_id, from, to)| diff_revisions(post_id, from, to),
+    );
+
+    let revisions_href = move || format!("/admin/posts/{}/revisions", post_id());
+    // One select per side of the diff; the empty value stands for the current content.
+    let revision_select = move |name: &'static str, revisions: &[PostRevision]| {
+        let selected = revision_param(name);
+        view! {
+            <select name=name class="select select-bordered select-sm">
+                <option value="" selected=selected.is_none()>"Current"</option>
+                {revisions.iter().map(|revision| view! {
+                    <option value=revision.id.to_string() selected=selected == Some(revision.id)>
+                        {format!("{} – {}", revision.created_at, revision.title)}
+                    </option>
+                }).collect::<Vec<_>>()}
+            </select>
+        }
+    };
+
+    view! {
+        <div class="flex justify-between items-center mb-4">
+            <h2 class="text-2xl font-semibold">"Revisions"</h2>
+            <A href=move || format!("/admin/posts/{}", post_id())>
+                <div class="btn btn-sm">"Back to editor"</div>
+            </A>
+        </div>
+        <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+            {move || match revisions.get() {
+                None => EitherOf4::A(view! { <div>"Loading..."</div> }),
+                Some(Ok(revisions)) if revisions.is_empty() => EitherOf4::B(view! {
+                    <div>"This post has not been edited yet."</div>
+                }),
+                Some(Ok(revisions)) => {
+                    let from_select = revision_select("from", &revisions);
+                    let to_select = revision_select("to", &revisions);
+                    EitherOf4::C(view! {
+                        <Form method="get" action=revisions_href()>
+                            <div class="flex flex-wrap items-center gap-2 mb-4">
+                                <span>"Compare"</span>
+                                {from_select}
+                                <span>"with"</span>
+                                {to_select}
+                                <button type="submit" class="btn btn-sm">"Diff"</button>
+                            </div>
+                        </Form>
+                        <table class="table w-full mb-8">
+                            <thead>
+                                <tr>
+                                    <th>"Saved"</th>
+                                    <th>"Title"</th>
+                                    <th></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {revisions.into_iter().map(|revision| {
+                                    let id = revision.id;
+                                    view! {
+                                        <tr>
+                                            <td class="text-sm">{revision.created_at}</td>
+                                            <td>{revision.title}</td>
+                                            <td class="text-right flex gap-2 justify-end">
+                                                <A href=move || format!("{}?from={id}", revisions_href())>
+                                                    <div class="btn btn-xs">"Diff with current"</div>
+                                                </A>
+                                                <button
+                                                    class="btn btn-xs btn-accent"
+                                                    disabled=move || restore.pending().get()
+                                                    on:click=move |_| {
+                                                        restore.dispatch(RestoreRevision { post_id: post_id(), revision_id: id });
+                                                    }
+                                                >
+                                                    "Restore"
+                                                </button>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    })
+                }
+                Some(Err(e)) => EitherOf4::D(view! {
+                    <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                        "Error loading revisions: " {e.to_string()}
+                    </div>
+                }),
+            }}
+        </Suspense>
+        <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+            {move || match diff.get() {
+                Some(Ok(Some(lines))) => EitherOf3::A(view! {
+                    <pre class="text-sm overflow-x-auto border border-base-300 rounded-lg p-4">
+                        {lines.into_iter().map(|line| {
+                            let (class, marker) = match line.tag {
+                                DiffTag::Equal => ("block", " "),
+                                DiffTag::Insert => ("block bg-success/20", "+"),
+                                DiffTag::Delete => ("block bg-error/20", "-"),
+                            };
+                            view! { <span class=class>{marker} " " {line.text}</span> }
+                        }).collect::<Vec<_>>()}
+                    </pre>
+                }),
+                Some(Err(e)) => EitherOf3::B(view! {
+                    <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                        "Error computing diff: " {e.to_string()}
+                    </div>
+                }),
+                _ => EitherOf3::C(()),
+            }}
+        </Suspense>
+    }
+}
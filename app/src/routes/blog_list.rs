@@ -1,60 +1,107 @@
 use leptos::prelude::*;
 use leptos::either::*;
+use leptos_router::components::{Form, A};
 use leptos_router::hooks::use_query_map;
+use leptos_router::params::ParamsMap;
 
-use crate::models::{post::BlogPost, category::Category};
+use crate::models::{post::{PostPage, PostSort}, category::{CategoryCount, CategoryFilter}};
 use crate::components::{post_card::PostCard, post_category::PostCategory};
 
-#[server(GetBlogPosts)]
-pub async fn get_blog_posts(category_slug: Option<String>) -> Result<Vec<BlogPost>, ServerFnError> {
+pub const POSTS_PER_PAGE: i64 = 9;
 
+#[server(GetBlogPosts)]
+pub async fn get_blog_posts(
+    filter: CategoryFilter,
+    sort: PostSort,
+    after: Option<String>,
+) -> Result<PostPage, ServerFnError> {
     use crate::state::AppState;
+    use crate::models::post::PostCursor;
 
     let state = expect_context::<AppState>();
 
-    let posts = match category_slug {
-        Some(slug) => state.db.search_posts_by_category(&slug).await,
-        None => state.db.get_all_posts_with_categories().await,
+    let after = match after {
+        Some(cursor) => match PostCursor::decode(&cursor) {
+            Some(cursor) => Some(cursor),
+            None => return Err(ServerFnError::Args(format!("invalid cursor: {cursor}"))),
+        },
+        None => None,
     };
 
-    posts.map_err(|e| ServerFnError::ServerError(e.to_string()))
+    let key = (filter.clone(), sort, after.map(|cursor| cursor.encode()));
+    state.cache.post_pages
+        .get_or_try_insert_with(key, || state.db.get_posts_page(&filter, None, sort, after, POSTS_PER_PAGE))
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
 }
 
+/// Every category with its number of listed posts.
 #[server(GetCategories)]
-pub async fn get_categories() -> Result<Vec<Category>, ServerFnError> {
+pub async fn get_categories() -> Result<Vec<CategoryCount>, ServerFnError> {
     use crate::state::AppState;
 
     let state = expect_context::<AppState>();
 
-    state.db.get_all_categories()
+    state.cache.categories
+        .get_or_try_insert_with((), || state.db.get_all_categories())
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))
 }
 
+/// `path` with the query `params` of the listing, plus the position of
+/// `page` when it is not the first one.
+fn page_href(path: &str, mut params: Vec<String>, page: usize, after: Option<&str>) -> String {
+    if let Some(after) = after {
+        params.push(format!("page={page}"));
+        params.push(format!("after={after}"));
+    }
+
+    if params.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{}", params.join("&"))
+    }
+}
+
+/// The 1-based page number shown for the `page` query parameter.
+pub fn current_page(query: Memo<ParamsMap>) -> impl Fn() -> usize + Copy {
+    move || {
+        query.with(|q| q.get("page"))
+            .and_then(|page| page.parse::<usize>().ok())
+            .filter(|page| *page > 0)
+            .unwrap_or(1)
+    }
+}
+
 #[component]
 pub fn BlogList() -> impl IntoView {
     let query = use_query_map();
-    let category = move || query.with(|q| q.get("category"));
-    let posts = Resource::new(category, get_blog_posts);
+    let filter = Memo::new(move |_| query.with(|q| CategoryFilter::from_query(q.get("category"), q.get("match"))));
+    let sort = Memo::new(move |_| query.with(|q| q.get("sort")).and_then(|sort| PostSort::parse(&sort)).unwrap_or_default());
+    let after = move || query.with(|q| q.get("after"));
+    let page = current_page(query);
+    let posts = Resource::new(
+        move || (filter.get(), sort(), after()),
+        |(filter, sort, after)| get_blog_posts(filter, sort, after),
+    );
     let categories = Resource::new(|| (), |_| get_categories());
 
-    let (selected_category, set_selected_category) = signal(None::<String>);
-
-    Effect::new(move |_| {
-        let current = query.with(|q| q.get("category"));
-        set_selected_category(current);
-    });
-
     view! {
         <div class="max-w-7xl mx-auto py-12 px-4 sm:px-6 lg:px-8">
-            <h1 class="text-3xl font-bold mb-4">"Latest Articles"</h1>
+            <div class="flex flex-wrap justify-between items-center gap-4 mb-4">
+                <h1 class="text-3xl font-bold">"Latest Articles"</h1>
+                <Form method="get" action="/search">
+                    <input type="search" name="q" class="input input-bordered input-sm" placeholder="Search posts..."/>
+                </Form>
+            </div>
             <Suspense>
                   {move || match categories.get() {
                       None => EitherOf3::A(view! { <div>"Loading categories..."</div> }),
                       Some(Ok(categories)) => EitherOf3::B(view! {
                           <PostCategory
                               categories=categories
-                              selected_category=selected_category
+                              filter=filter
+                              sort=sort
                           />
                       }),
                       Some(Err(e)) => EitherOf3::C(view! {
@@ -62,29 +109,21 @@ pub fn BlogList() -> impl IntoView {
                       }),
                   }}
             </Suspense>
+            <div class="join mb-8">
+                {PostSort::ALL.into_iter().map(|option| view! {
+                    <A href=move || filter.with(|filter| page_href("/blog", filter.query_params(option), 1, None))>
+                        <div class=move || if sort() == option { "btn btn-sm join-item btn-accent" } else { "btn btn-sm join-item" }>
+                            {option.label()}
+                        </div>
+                    </A>
+                }).collect::<Vec<_>>()}
+            </div>
 
-            <Suspense
-                fallback=move || view! {
-                    <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
-                        {(0..3).map(|_| view! {
-                            <div class="flex w-52 flex-col gap-4">
-                                <div class="skeleton h-32 w-full"></div>
-                                <div class="skeleton h-4 w-28"></div>
-                                <div class="skeleton h-4 w-full"></div>
-                                <div class="skeleton h-4 w-full"></div>
-                            </div>
-                        }).collect::<Vec<_>>()}
-                    </div>
-                }
-            >
+            <Suspense fallback=PostGridSkeleton>
                 {move || match posts.get() {
                     None => EitherOf3::A(view! { <div>"Loading..."</div> }),
                     Some(Ok(posts)) => EitherOf3::B(view! {
-                        <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
-                            {posts.into_iter()
-                                .map(|post| view! { <PostCard post=post/> })
-                                .collect::<Vec<_>>()}
-                        </div>
+                        <PostGrid posts page=page() path="/blog" params=filter.with(|filter| filter.query_params(sort())) sort=sort()/>
                     }),
                     Some(Err(e)) => EitherOf3::C(view! {
                         <div class="text-red-500 p-4 bg-red-50 rounded-lg">
@@ -96,3 +135,57 @@ pub fn BlogList() -> impl IntoView {
         </div>
     }
 }
+
+#[component]
+pub fn PostGridSkeleton() -> impl IntoView {
+    view! {
+        <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
+            {(0..3).map(|_| view! {
+                <div class="flex w-52 flex-col gap-4">
+                    <div class="skeleton h-32 w-full"></div>
+                    <div class="skeleton h-4 w-28"></div>
+                    <div class="skeleton h-4 w-full"></div>
+                    <div class="skeleton h-4 w-full"></div>
+                </div>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+/// One page of post cards with links to the first and the next page of the
+/// listing at `path` narrowed by the query `params` and ordered by `sort`.
+#[component]
+pub fn PostGrid(
+    posts: PostPage,
+    page: usize,
+    #[prop(into)] path: String,
+    #[prop(optional)] params: Vec<String>,
+    #[prop(optional)] sort: PostSort,
+) -> impl IntoView {
+    let PostPage { posts, next_cursor } = posts;
+    let (first_label, next_label) = match sort {
+        PostSort::Newest => ("Latest", "Older posts"),
+        PostSort::Shortest | PostSort::Longest => ("First page", "Next page"),
+    };
+
+    view! {
+        <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
+            {posts.into_iter()
+                .map(|post| view! { <PostCard post=post/> })
+                .collect::<Vec<_>>()}
+        </div>
+        <div class="flex justify-center items-center gap-4 mt-8 mb-12">
+            {(page > 1).then(|| view! {
+                <A href=page_href(&path, params.clone(), 1, None)>
+                    <div class="btn btn-sm">{first_label}</div>
+                </A>
+            })}
+            <span class="text-sm">"Page " {page}</span>
+            {next_cursor.map(|cursor| view! {
+                <A href=page_href(&path, params, page + 1, Some(&cursor))>
+                    <div class="btn btn-sm btn-accent">{next_label}</div>
+                </A>
+            })}
+        </div>
+    }
+}
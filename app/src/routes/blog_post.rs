@@ -1,26 +1,100 @@
 use leptos::prelude::*;
 use leptos::either::*;
-use leptos_router::hooks::use_params_map;
+use leptos_router::hooks::{use_params_map, use_query_map};
 use crate::models::post::BlogPost;
-use crate::components::post::Post;
+use crate::components::post::{Post, PostMeta};
 use super::page_not_found::PageNotFound;
+use super::{set_page_status, skip_dry_run};
 
+/// Fetches a publicly visible post, or any post at all when `preview`
+/// carries its secret preview token, along with the series it is a part
+/// of, the posts published around it, related posts and the site's public
+/// origin. Slugs a post has since been renamed from permanently redirect to
+/// its current slug. `hero_image` is cleared when it points at a missing
+/// local file. Published posts are served from the read cache.
 #[server(GetBlogPost)]
-pub async fn get_blog_post(slug: String) -> Result<BlogPost, ServerFnError> {
+pub async fn get_blog_post(slug: String, preview: Option<String>) -> Result<Option<BlogPost>, ServerFnError> {
     use crate::state::AppState;
 
+    /// How many related posts are shown below a post.
+    const RELATED_POSTS_LIMIT: usize = 3;
+
     let state = expect_context::<AppState>();
 
-    state.db.get_post_by_slug(&slug)
-        .await
-        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    let is_preview = preview.is_some();
+    if !is_preview {
+        if let Some(post) = state.cache.posts.get(&slug) {
+            return Ok(Some(post));
+        }
+    }
+
+    let mut post = match preview {
+        Some(token) => state.db.get_post_preview(&slug, &token).await?,
+        None => state.db.get_post_by_slug(&slug).await?,
+    };
+
+    // Local hero images that were never uploaded would only render as broken
+    // images; dropping them also makes the generated share card kick in.
+    if let Some(post) = post.as_mut() {
+        if post.hero_image.starts_with('/') && !post.hero_image.contains("..") {
+            let path = std::path::Path::new(&*state.leptos_options.site_root).join(post.hero_image.trim_start_matches('/'));
+            if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+                post.hero_image.clear();
+            }
+        }
+    }
+
+    if let Some(post) = post.as_mut() {
+        post.series = state.db.get_post_series(post.id).await?;
+        (post.previous, post.next) = state.db.get_adjacent_posts(post.id).await?;
+
+        // Related posts already linked as previous or next would be shown twice.
+        let adjacent = [&post.previous, &post.next].map(|adjacent| adjacent.as_ref().map(|p| p.id));
+        post.related = state.db.get_related_posts(post.id, RELATED_POSTS_LIMIT as i64 + 2).await?;
+        post.related.retain(|related| !adjacent.contains(&Some(related.id)));
+        post.related.truncate(RELATED_POSTS_LIMIT);
+        post.site_url = state.site_url.clone();
+    }
+
+    match &post {
+        Some(post) if !is_preview => state.cache.posts.insert(slug, post.clone()),
+        Some(_) => (),
+        None => {
+            if let Some(current) = state.db.get_current_slug(&slug).await? {
+                redirect_permanently(&format!("/blog/{current}"));
+            }
+        }
+    }
+
+    Ok(post)
+}
+
+/// Like `leptos_axum::redirect`, but answers page loads with a
+/// `301 Moved Permanently` instead of a `302 Found`.
+#[cfg(feature = "ssr")]
+fn redirect_permanently(path: &str) {
+    use axum::http::StatusCode;
+    use leptos_axum::ResponseOptions;
+
+    leptos_axum::redirect(path);
+    if let Some(response) = use_context::<ResponseOptions>() {
+        let mut parts = response.0.write();
+        if parts.status == Some(StatusCode::FOUND) {
+            parts.status = Some(StatusCode::MOVED_PERMANENTLY);
+        }
+    }
 }
 
 #[component]
 pub fn BlogPost() -> impl IntoView {
     let params = use_params_map();
+    let query = use_query_map();
     let slug = move || params.with(|params| params.get("slug").unwrap_or_default());
-    let post = Resource::new(slug, get_blog_post);
+    let preview = move || query.with(|query| query.get("preview"));
+    let post = Resource::new(
+        move || (slug(), preview()),
+        |(slug, preview)| get_blog_post(slug, preview),
+    );
 
     view! {
         <Suspense
@@ -37,12 +111,26 @@ pub fn BlogPost() -> impl IntoView {
                 </div>
             }
         >
+            // Meta tags are registered as soon as they are built, so build
+            // them once the post has loaded, and only once.
+            {move || Suspend::new(async move {
+                skip_dry_run().await;
+                post.await.ok().flatten().map(|post| view! { <PostMeta post/> })
+            })}
             {move || match post.get() {
-                None => EitherOf3::A(view! { <div>"Loading..."</div> }),
-                Some(Ok(post)) => EitherOf3::B(view! { <Post post /> }),
-                Some(Err(_)) => EitherOf3::C(view! {
+                None => EitherOf4::A(view! { <div>"Loading..."</div> }),
+                Some(Ok(Some(post))) => EitherOf4::B(view! { <Post post /> }),
+                Some(Ok(None)) => EitherOf4::C(view! {
                     <PageNotFound/>
                 }),
+                Some(Err(e)) => {
+                    set_page_status(500);
+                    EitherOf4::D(view! {
+                        <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                            "Error loading post: " {e.to_string()}
+                        </div>
+                    })
+                }
             }}
         </Suspense>
     }
@@ -0,0 +1,103 @@
+use leptos::prelude::*;
+use leptos::either::*;
+use leptos_meta::*;
+use leptos_router::components::A;
+use leptos_router::hooks::{use_params_map, use_query_map};
+use crate::models::{category::{Category, CategoryFilter}, post::PostSort};
+use crate::SITE_TITLE;
+use super::blog_list::{current_page, get_blog_posts, PostGrid, PostGridSkeleton};
+use super::page_not_found::PageNotFound;
+use super::{set_page_status, skip_dry_run};
+
+#[server(GetCategory)]
+pub async fn get_category(slug: String) -> Result<Option<Category>, ServerFnError> {
+    use crate::state::AppState;
+
+    let state = expect_context::<AppState>();
+
+    state.db.get_category_by_slug(&slug)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Landing page of a category: its name, description and posts.
+#[component]
+pub fn CategoryPage() -> impl IntoView {
+    let params = use_params_map();
+    let query = use_query_map();
+    let slug = move || params.with(|params| params.get("slug").unwrap_or_default());
+    let after = move || query.with(|q| q.get("after"));
+    let page = current_page(query);
+    let category = Resource::new(slug, get_category);
+    let posts = Resource::new(
+        move || (slug(), after()),
+        |(slug, after)| get_blog_posts(CategoryFilter::single(slug), PostSort::Newest, after),
+    );
+
+    view! {
+        <Suspense fallback=PostGridSkeleton>
+            // Meta tags are registered as soon as they are built, so only
+            // build them once the category has loaded, and only once.
+            {move || Suspend::new(async move {
+                skip_dry_run().await;
+                category.await.ok().flatten().map(|category| {
+                    let title = format!("{} | {SITE_TITLE}", category.name);
+                    let feed_href = format!("{}/feed.xml", category.href());
+                    let description = category.description
+                        .unwrap_or_else(|| format!("Posts filed under {}", category.name));
+                    view! {
+                        <Title text=title/>
+                        <Meta name="description" content=description/>
+                        <Link rel="alternate" type_="application/rss+xml" title=category.name href=feed_href/>
+                    }
+                })
+            })}
+            {move || match category.get() {
+                None => EitherOf4::A(view! { <div>"Loading..."</div> }),
+                Some(Ok(Some(category))) => EitherOf4::B({
+                    let href = category.href();
+                    view! {
+                        <div class="max-w-7xl mx-auto py-12 px-4 sm:px-6 lg:px-8">
+                            <div class="flex flex-wrap justify-between items-center gap-4 mb-4">
+                                <h1 class="text-3xl font-bold">{category.name.clone()}</h1>
+                                <div class="flex gap-2">
+                                    <a href=format!("{href}/feed.xml")>
+                                        <div class="btn btn-sm">"RSS"</div>
+                                    </a>
+                                    <A href="/blog">
+                                        <div class="btn btn-sm">"All posts"</div>
+                                    </A>
+                                </div>
+                            </div>
+                            {category.description.map(|description| view! {
+                                <p class="mb-8">{description}</p>
+                            })}
+                            <Suspense fallback=PostGridSkeleton>
+                                {move || match posts.get() {
+                                    None => EitherOf3::A(view! { <div>"Loading..."</div> }),
+                                    Some(Ok(posts)) => EitherOf3::B(view! {
+                                        <PostGrid posts page=page() path=href.clone()/>
+                                    }),
+                                    Some(Err(e)) => EitherOf3::C(view! {
+                                        <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                                            "Error loading posts: " {e.to_string()}
+                                        </div>
+                                    }),
+                                }}
+                            </Suspense>
+                        </div>
+                    }
+                }),
+                Some(Ok(None)) => EitherOf4::C(view! { <PageNotFound/> }),
+                Some(Err(e)) => {
+                    set_page_status(500);
+                    EitherOf4::D(view! {
+                        <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                            "Error loading category: " {e.to_string()}
+                        </div>
+                    })
+                }
+            }}
+        </Suspense>
+    }
+}
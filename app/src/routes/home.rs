@@ -1,6 +1,7 @@
 use leptos::prelude::*;
 use leptos::ev::{keydown, KeyboardEvent};
 use leptos_router::{hooks::use_navigate, NavigateOptions};
+use crate::components::search_palette::{is_typing, SearchPaletteOpen};
 use crate::routes::Routes;
 
 const NAV_ELEMENTS: [(Routes, &str, &str, &str); 3] = [
@@ -24,7 +25,15 @@ pub fn HomePage() -> impl IntoView {
         navigate(href, NavigateOptions::default());
     };
 
+    let palette_open = use_context::<SearchPaletteOpen>();
     window_event_listener(keydown, move |e: KeyboardEvent| {
+        // Leave keys to the search palette while it is open, and to its
+        // `Ctrl+K` shortcut and any focused text field.
+        let palette_is_open = palette_open.is_some_and(|SearchPaletteOpen(open)| open.get_untracked());
+        if palette_is_open || e.ctrl_key() || e.meta_key() || e.alt_key() || is_typing(&e) {
+            return;
+        }
+
         match e.key().as_str() {
             "j" => cycle_selection(1),
             "ArrowDown" => cycle_selection(1),
@@ -2,6 +2,10 @@ pub mod home;
 pub mod blog_list;
 pub mod blog_post;
 pub mod page_not_found;
+pub mod admin;
+pub mod search;
+pub mod category;
+pub mod tag;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Routes {
@@ -9,3 +13,106 @@ pub enum Routes {
    Blog,
    Projects,
 }
+
+/// Sets the HTTP status of the page being server-rendered, unless a
+/// redirect was already issued while loading its data. Does nothing when
+/// rendering in the browser.
+pub fn set_page_status(status: u16) {
+    #[cfg(feature = "ssr")]
+    {
+        use axum::http::StatusCode;
+        use leptos::prelude::use_context;
+        use leptos_axum::ResponseOptions;
+
+        if let (Some(response), Ok(status)) = (use_context::<ResponseOptions>(), StatusCode::from_u16(status)) {
+            let mut parts = response.0.write();
+            if !parts.status.is_some_and(|status| status.is_redirection()) {
+                parts.status = Some(status);
+            }
+        }
+    }
+    #[cfg(not(feature = "ssr"))]
+    let _ = status;
+}
+
+/// Completes on its second poll, so a `Suspend` awaiting it first builds
+/// its view at most once per render.
+///
+/// During async SSR, leptos 0.7.0-rc2's `Suspense` calls its children's
+/// closure twice: once for a dry run that registers the resources they
+/// read, and again to render them. Each call creates a new `Suspend`, and
+/// the dry run polls it once, running it to completion when its resources
+/// have already loaded. Building a view registers its meta tags with the
+/// page head, so without this they would be registered by both calls.
+/// Waking itself before returning `Pending` keeps it safe outside that dry
+/// run: any executor polls it again straight away.
+pub async fn skip_dry_run() {
+    YieldOnce(false).await
+}
+
+struct YieldOnce(bool);
+
+impl std::future::Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<()> {
+        if self.0 {
+            std::task::Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+    use leptos::prelude::*;
+    use leptos::task::Executor;
+
+    use super::skip_dry_run;
+
+    /// Times the view of a `Suspend` that is ready as soon as it is polled
+    /// gets built while its `Suspense` renders for async SSR.
+    fn ready_suspend_builds(skip: bool) -> usize {
+        _ = Executor::init_futures_executor();
+        let builds = Arc::new(AtomicUsize::new(0));
+
+        Owner::new().with(|| {
+            let builds = builds.clone();
+            let view = view! {
+                <Suspense>
+                    {move || {
+                        let builds = builds.clone();
+                        Suspend::new(async move {
+                            if skip {
+                                skip_dry_run().await;
+                            }
+                            builds.fetch_add(1, Ordering::Relaxed);
+                        })
+                    }}
+                </Suspense>
+            };
+            futures::executor::block_on(view.to_html_stream_in_order().collect::<String>())
+        });
+
+        builds.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn suspense_builds_ready_views_twice() {
+        // Once this fails, leptos no longer runs ready `Suspend`s in the
+        // dry run and `skip_dry_run` can go.
+        assert_eq!(ready_suspend_builds(false), 2);
+    }
+
+    #[test]
+    fn skip_dry_run_builds_ready_views_once() {
+        assert_eq!(ready_suspend_builds(true), 1);
+    }
+}
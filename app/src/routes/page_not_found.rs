@@ -1,11 +1,11 @@
 use leptos::prelude::*;
 use crate::components::flappy_bird::FlappyBird;
 use crate::models::flappy_bird::GameAssets;
+use super::set_page_status;
 
 #[server(GetGameAssets)]
 pub async fn get_game_assets() -> Result<GameAssets, ServerFnError> {
     use leptos::logging::log;
-    leptos_axum::redirect("/404");
     log!("Server: Sending game assets");
     Ok(GameAssets {
         bird_frames: vec![
@@ -23,6 +23,8 @@ pub async fn get_game_assets() -> Result<GameAssets, ServerFnError> {
 
 #[component]
 pub fn PageNotFound() -> impl IntoView {
+    set_page_status(404);
+
     let dialog_ref = NodeRef::<leptos::html::Dialog>::new();
     let (show_game, set_show_game) = signal(false);
 
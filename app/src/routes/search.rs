@@ -0,0 +1,72 @@
+use leptos::prelude::*;
+use leptos::either::*;
+use leptos_router::components::Form;
+use leptos_router::hooks::use_query_map;
+use crate::models::post::SearchResult;
+use crate::components::post_card::PostCard;
+
+#[cfg(feature = "ssr")]
+const SEARCH_RESULTS: i64 = 30;
+
+#[server(SearchPosts)]
+pub async fn search_posts(query: String) -> Result<Vec<SearchResult>, ServerFnError> {
+    use crate::state::AppState;
+
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let state = expect_context::<AppState>();
+
+    state.db.search(&query, SEARCH_RESULTS)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[component]
+pub fn SearchPage() -> impl IntoView {
+    let query = use_query_map();
+    let q = move || query.with(|query| query.get("q")).unwrap_or_default();
+    let results = Resource::new(q, search_posts);
+
+    view! {
+        <div class="max-w-7xl mx-auto py-12 px-4 sm:px-6 lg:px-8">
+            <h1 class="text-3xl font-bold mb-4">"Search"</h1>
+            <Form method="get" action="/search">
+                <div class="join w-full max-w-xl mb-8">
+                    <input
+                        type="search"
+                        name="q"
+                        class="input input-bordered join-item w-full"
+                        placeholder="Search posts..."
+                        prop:value=q
+                    />
+                    <button type="submit" class="btn btn-accent join-item">"Search"</button>
+                </div>
+            </Form>
+            <Suspense fallback=move || view! { <div>"Searching..."</div> }>
+                {move || match results.get() {
+                    None => EitherOf4::A(view! { <div>"Searching..."</div> }),
+                    Some(Ok(results)) if results.is_empty() => EitherOf4::B(view! {
+                        <div>{move || if q().trim().is_empty() { "Type something to search for." } else { "No posts found." }}</div>
+                    }),
+                    Some(Ok(results)) => EitherOf4::C(view! {
+                        <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
+                            {results.into_iter().map(|SearchResult { post, snippet }| view! {
+                                <div class="flex flex-col gap-2">
+                                    <PostCard post=post/>
+                                    <p class="text-sm px-2 [&_mark]:bg-accent [&_mark]:text-accent-content" inner_html=snippet></p>
+                                </div>
+                            }).collect::<Vec<_>>()}
+                        </div>
+                    }),
+                    Some(Err(e)) => EitherOf4::D(view! {
+                        <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                            "Error searching posts: " {e.to_string()}
+                        </div>
+                    }),
+                }}
+            </Suspense>
+        </div>
+    }
+}
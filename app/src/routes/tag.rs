@@ -0,0 +1,106 @@
+use leptos::prelude::*;
+use leptos::either::*;
+use leptos_meta::*;
+use leptos_router::components::A;
+use leptos_router::hooks::{use_params_map, use_query_map};
+use crate::models::{post::PostPage, tag::Tag};
+use crate::SITE_TITLE;
+use super::blog_list::{current_page, PostGrid, PostGridSkeleton};
+use super::page_not_found::PageNotFound;
+use super::{set_page_status, skip_dry_run};
+
+#[server(GetTag)]
+pub async fn get_tag(slug: String) -> Result<Option<Tag>, ServerFnError> {
+    use crate::state::AppState;
+
+    let state = expect_context::<AppState>();
+
+    state.db.get_tag_by_slug(&slug)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+#[server(GetTagPosts)]
+pub async fn get_tag_posts(slug: String, after: Option<String>) -> Result<PostPage, ServerFnError> {
+    use crate::state::AppState;
+    use crate::models::{category::CategoryFilter, post::{PostCursor, PostSort}};
+    use super::blog_list::POSTS_PER_PAGE;
+
+    let state = expect_context::<AppState>();
+
+    let after = match after {
+        Some(cursor) => match PostCursor::decode(&cursor) {
+            Some(cursor) => Some(cursor),
+            None => return Err(ServerFnError::Args(format!("invalid cursor: {cursor}"))),
+        },
+        None => None,
+    };
+
+    state.db.get_posts_page(&CategoryFilter::default(), Some(&slug), PostSort::Newest, after, POSTS_PER_PAGE)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Lists the posts carrying a tag.
+#[component]
+pub fn TagPage() -> impl IntoView {
+    let params = use_params_map();
+    let query = use_query_map();
+    let slug = move || params.with(|params| params.get("slug").unwrap_or_default());
+    let after = move || query.with(|q| q.get("after"));
+    let page = current_page(query);
+    let tag = Resource::new(slug, get_tag);
+    let posts = Resource::new(
+        move || (slug(), after()),
+        |(slug, after)| get_tag_posts(slug, after),
+    );
+
+    view! {
+        <Suspense fallback=PostGridSkeleton>
+            // Meta tags are registered as soon as they are built, so only
+            // build them once the tag has loaded, and only once.
+            {move || Suspend::new(async move {
+                skip_dry_run().await;
+                tag.await.ok().flatten().map(|tag| view! {
+                    <Title text=format!("#{} | {SITE_TITLE}", tag.name)/>
+                    <Meta name="description" content=format!("Posts tagged {}", tag.name)/>
+                })
+            })}
+            {move || match tag.get() {
+                None => EitherOf4::A(view! { <div>"Loading..."</div> }),
+                Some(Ok(Some(tag))) => EitherOf4::B(view! {
+                    <div class="max-w-7xl mx-auto py-12 px-4 sm:px-6 lg:px-8">
+                        <div class="flex flex-wrap justify-between items-center gap-4 mb-8">
+                            <h1 class="text-3xl font-bold">{format!("#{}", tag.name)}</h1>
+                            <A href="/blog">
+                                <div class="btn btn-sm">"All posts"</div>
+                            </A>
+                        </div>
+                        <Suspense fallback=PostGridSkeleton>
+                            {move || match posts.get() {
+                                None => EitherOf3::A(view! { <div>"Loading..."</div> }),
+                                Some(Ok(posts)) => EitherOf3::B(view! {
+                                    <PostGrid posts page=page() path=tag.href()/>
+                                }),
+                                Some(Err(e)) => EitherOf3::C(view! {
+                                    <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                                        "Error loading posts: " {e.to_string()}
+                                    </div>
+                                }),
+                            }}
+                        </Suspense>
+                    </div>
+                }),
+                Some(Ok(None)) => EitherOf4::C(view! { <PageNotFound/> }),
+                Some(Err(e)) => {
+                    set_page_status(500);
+                    EitherOf4::D(view! {
+                        <div class="text-red-500 p-4 bg-red-50 rounded-lg">
+                            "Error loading tag: " {e.to_string()}
+                        </div>
+                    })
+                }
+            }}
+        </Suspense>
+    }
+}
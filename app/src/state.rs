@@ -1,13 +1,24 @@
 use sqlx::PgPool;
 use axum::extract::FromRef;
 use leptos::prelude::{LeptosOptions, ServerFnError};
-use crate::db;
+use crate::{auth, cache, db};
+use crate::models::search_index::SearchIndexCache;
 
 #[derive(FromRef, Clone, Debug)]
 pub struct AppState {
     pub leptos_options: LeptosOptions,
+    /// Public origin of the site, without a trailing slash, used for
+    /// absolute links in feeds.
+    pub site_url: String,
+    /// Paths `robots.txt` asks crawlers to stay out of.
+    pub robots_disallow: Vec<String>,
     pub pool: PgPool,
     pub db: db::PostRepository,
+    pub auth: db::AuthRepository,
+    /// The instant search index, cleared by admin writes.
+    pub search_index: SearchIndexCache,
+    /// Recent reads behind the public pages, cleared by admin writes.
+    pub cache: cache::ReadCache,
 }
 
 impl AppState {
@@ -22,7 +33,42 @@ impl AppState {
 
         let pool = PgPool::connect(&database_url).await?;
         let db = db::PostRepository::new(pool.clone());
+        let auth = db::AuthRepository::new(pool.clone());
 
-        Ok(Self { leptos_options, pool, db })
+        // The admin account is provisioned from the environment on startup,
+        // so rotating the secret and restarting resets the password.
+        let admin_password = match std::fs::read_to_string("/run/secrets/admin_password") {
+            Ok(secret) => Some(secret.trim().to_string()),
+            Err(_) => std::env::var("ADMIN_PASSWORD").ok(),
+        };
+
+        if let (Ok(username), Some(password)) = (std::env::var("ADMIN_USERNAME"), admin_password) {
+            auth.upsert_admin(&username, &auth::hash_password(&password)?).await?;
+        }
+
+        let site_url = std::env::var("SITE_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| format!("http://{}", leptos_options.site_addr))
+            .trim_end_matches('/')
+            .to_string();
+
+        // A comma-separated list; set it empty to allow everything.
+        let robots_disallow = std::env::var("ROBOTS_DISALLOW")
+            .unwrap_or_else(|_| "/admin,/api".to_string())
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        // How long public reads are served from memory; 0 disables caching.
+        let cache_ttl = std::env::var("CACHE_TTL_SECONDS")
+            .ok()
+            .and_then(|seconds| seconds.trim().parse().ok())
+            .unwrap_or(60);
+        let cache = cache::ReadCache::new(std::time::Duration::from_secs(cache_ttl));
+
+        Ok(Self { leptos_options, site_url, robots_disallow, pool, db, auth, search_index: SearchIndexCache::default(), cache })
     }
 }
@@ -0,0 +1,16 @@
+-- Create admin_users table
+CREATE TABLE admin_users (
+    id BIGSERIAL PRIMARY KEY,
+    username VARCHAR(100) NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL
+);
+
+-- Create admin_sessions table, keyed by the opaque cookie token
+CREATE TABLE admin_sessions (
+    token VARCHAR(64) PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES admin_users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX admin_sessions_expires_at_idx ON admin_sessions(expires_at);
@@ -0,0 +1,12 @@
+-- Publication state of a post
+CREATE TYPE post_status AS ENUM ('draft', 'scheduled', 'published', 'archived');
+
+-- Existing posts were all public, so backfill them as published
+ALTER TABLE blog_posts
+    ADD COLUMN status post_status NOT NULL DEFAULT 'published',
+    ADD COLUMN preview_token VARCHAR(64) NOT NULL UNIQUE
+        DEFAULT REPLACE(gen_random_uuid()::TEXT, '-', '');
+
+ALTER TABLE blog_posts ALTER COLUMN status SET DEFAULT 'draft';
+
+CREATE INDEX blog_posts_status_published_at_idx ON blog_posts(status, published_at DESC);
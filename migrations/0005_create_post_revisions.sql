@@ -0,0 +1,11 @@
+-- Snapshot of a post's text taken before each update
+CREATE TABLE blog_post_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    blog_post_id BIGINT NOT NULL REFERENCES blog_posts(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX blog_post_revisions_post_idx ON blog_post_revisions(blog_post_id, created_at DESC);
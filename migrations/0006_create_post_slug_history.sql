@@ -0,0 +1,8 @@
+-- Slugs a post was previously published under, kept so old links can redirect
+CREATE TABLE post_slug_history (
+    slug VARCHAR(255) PRIMARY KEY,
+    blog_post_id BIGINT NOT NULL REFERENCES blog_posts(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX post_slug_history_post_idx ON post_slug_history(blog_post_id);
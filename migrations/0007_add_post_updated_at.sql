@@ -0,0 +1,4 @@
+-- Last time a post was edited, used for feed and sitemap freshness
+ALTER TABLE blog_posts ADD COLUMN updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW();
+
+UPDATE blog_posts SET updated_at = published_at;
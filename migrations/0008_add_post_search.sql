@@ -0,0 +1,8 @@
+-- Full-text search document, weighting the title above the description above the content
+ALTER TABLE blog_posts ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', description), 'B') ||
+    setweight(to_tsvector('english', content), 'C')
+) STORED;
+
+CREATE INDEX blog_posts_search_vector_idx ON blog_posts USING GIN (search_vector);
@@ -0,0 +1,15 @@
+-- Free-form tags, created on the fly from the names typed in the post editor
+CREATE TABLE tags (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    slug VARCHAR(100) NOT NULL UNIQUE
+);
+
+-- Create junction table for blog_posts and tags
+CREATE TABLE blog_posts_tags (
+    blog_post_id BIGINT REFERENCES blog_posts(id) ON DELETE CASCADE,
+    tag_id BIGINT REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (blog_post_id, tag_id)
+);
+
+CREATE INDEX blog_posts_tags_tag_idx ON blog_posts_tags(tag_id);
@@ -0,0 +1,15 @@
+-- Multi-part series, created on the fly from the title typed in the post editor
+CREATE TABLE series (
+    id BIGSERIAL PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    slug VARCHAR(255) NOT NULL UNIQUE,
+    description TEXT
+);
+
+-- A post belongs to at most one series, at a position unique within it
+CREATE TABLE blog_posts_series (
+    blog_post_id BIGINT PRIMARY KEY REFERENCES blog_posts(id) ON DELETE CASCADE,
+    series_id BIGINT NOT NULL REFERENCES series(id) ON DELETE CASCADE,
+    position INT NOT NULL CHECK (position > 0),
+    UNIQUE (series_id, position)
+);
@@ -0,0 +1,22 @@
+-- Words of prose in a post. The count skips code blocks and markup, so the
+-- server computes it on every write. Existing posts are counted here by
+-- stripping fenced code blocks, images and HTML tags and counting what is
+-- left, which saving them again makes exact.
+ALTER TABLE blog_posts ADD COLUMN word_count INT;
+
+UPDATE blog_posts
+SET word_count = (
+    SELECT COUNT(*)
+    FROM regexp_split_to_table(
+        regexp_replace(
+            regexp_replace(
+                regexp_replace(
+                    regexp_replace(content, '```.*?```', ' ', 'g'),
+                    '~~~.*?~~~', ' ', 'g'),
+                '!\[[^]]*\]\([^)]*\)', ' ', 'g'),
+            '<[^>]*>', ' ', 'g'),
+        '\s+') AS word
+    WHERE word ~ '[[:alnum:]]'
+);
+
+ALTER TABLE blog_posts ALTER COLUMN word_count SET NOT NULL;
@@ -0,0 +1,9 @@
+-- HTML rendered from each post's markdown, reused for as long as the content
+-- hash and the renderer version it was rendered with still match
+CREATE TABLE post_renders (
+    blog_post_id BIGINT PRIMARY KEY REFERENCES blog_posts(id) ON DELETE CASCADE,
+    content_hash TEXT NOT NULL,
+    renderer_version INT NOT NULL,
+    content TEXT NOT NULL,
+    toc TEXT
+);
@@ -0,0 +1,5 @@
+-- Whether a post shows up in listings, feeds and the sitemap: scheduled or
+-- published, and already past its publish date
+CREATE FUNCTION is_listed(p blog_posts) RETURNS BOOLEAN
+    LANGUAGE sql STABLE
+    AS $$ SELECT p.status IN ('scheduled', 'published') AND p.published_at <= NOW() $$;
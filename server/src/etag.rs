@@ -0,0 +1,11 @@
+use axum::http::{header, HeaderMap};
+
+/// Whether the request's `If-None-Match` is `*` or lists `etag`, comparing
+/// tags weakly as `GET` and `HEAD` requests call for, so the client's copy
+/// is current. `None` when the request sends no usable `If-None-Match`.
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> Option<bool> {
+    let value = headers.get(header::IF_NONE_MATCH)?.to_str().ok()?;
+    let etag = etag.trim_start_matches("W/");
+
+    Some(value.trim() == "*" || value.split(',').any(|tag| tag.trim().trim_start_matches("W/") == etag))
+}
@@ -0,0 +1,327 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use app::models::category::CategoryFilter;
+use app::db::RepositoryError;
+use app::models::post::BlogPost;
+use app::state::AppState;
+use atom_syndication::{
+    CategoryBuilder as AtomCategoryBuilder, ContentBuilder, EntryBuilder, FeedBuilder, FixedDateTime, LinkBuilder,
+    PersonBuilder, Text,
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use rss::{CategoryBuilder, ChannelBuilder, GuidBuilder, ItemBuilder};
+use serde::Serialize;
+
+use crate::etag::if_none_match;
+
+const FEED_TITLE: &str = app::SITE_TITLE;
+const FEED_AUTHOR: &str = app::SITE_AUTHOR;
+const FEED_LENGTH: usize = 20;
+
+const RSS_CONTENT_TYPE: &str = "application/rss+xml; charset=utf-8";
+const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+const JSON_FEED_CONTENT_TYPE: &str = "application/feed+json; charset=utf-8";
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+/// Posts and metadata shared by every feed format.
+struct Feed {
+    site_url: String,
+    title: String,
+    description: String,
+    /// Absolute URL of the page the feed mirrors.
+    link: String,
+    /// Absolute URL of the feed document itself.
+    self_link: String,
+    posts: Vec<BlogPost>,
+}
+
+pub async fn rss(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    match site_feed(&state, "/feed.xml").await {
+        Ok(feed) => feed_response(&headers, &feed, RSS_CONTENT_TYPE, render_rss(&feed)),
+        Err(e) => internal_error(e),
+    }
+}
+
+pub async fn atom(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    match site_feed(&state, "/atom.xml").await {
+        Ok(feed) => feed_response(&headers, &feed, ATOM_CONTENT_TYPE, render_atom(&feed)),
+        Err(e) => internal_error(e),
+    }
+}
+
+pub async fn json(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    match site_feed(&state, "/feed.json").await {
+        Ok(feed) => feed_response(&headers, &feed, JSON_FEED_CONTENT_TYPE, render_json_feed(&feed)),
+        Err(e) => internal_error(e),
+    }
+}
+
+pub async fn category_rss(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let category = match state.db.get_category_by_slug(&slug).await {
+        Ok(Some(category)) => category,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => return internal_error(e.into()),
+    };
+
+    let filter = CategoryFilter::single(category.slug.clone());
+    let posts = match state.db.search_posts_by_category(&filter, FEED_LENGTH as i64).await {
+        Ok(posts) => posts,
+        Err(e) => return internal_error(e),
+    };
+
+    let feed = Feed {
+        site_url: state.site_url.clone(),
+        title: format!("{FEED_TITLE} – {}", category.name),
+        link: format!("{}{}", state.site_url, category.href()),
+        self_link: format!("{}{}/feed.xml", state.site_url, category.href()),
+        description: category
+            .description
+            .unwrap_or_else(|| format!("Posts filed under {}", category.name)),
+        posts,
+    };
+
+    feed_response(&headers, &feed, RSS_CONTENT_TYPE, render_rss(&feed))
+}
+
+async fn site_feed(state: &AppState, path: &str) -> Result<Feed, RepositoryError> {
+    Ok(Feed {
+        site_url: state.site_url.clone(),
+        title: FEED_TITLE.to_string(),
+        description: format!("Latest posts from {FEED_TITLE}"),
+        link: format!("{}/blog", state.site_url),
+        self_link: format!("{}{path}", state.site_url),
+        posts: state.db.get_latest_posts(FEED_LENGTH as i64).await?,
+    })
+}
+
+impl Feed {
+    fn post_url(&self, post: &BlogPost) -> String {
+        format!("{}/blog/{}", self.site_url, post.slug)
+    }
+
+    /// Resolves site-relative paths such as hero images against the site URL.
+    fn absolute_url(&self, path: &str) -> String {
+        if path.starts_with('/') {
+            format!("{}{path}", self.site_url)
+        } else {
+            path.to_string()
+        }
+    }
+
+    /// The feed changes whenever a post is published or edited.
+    fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.posts
+            .iter()
+            .flat_map(|post| [&post.published_at_rfc3339, &post.updated_at_rfc3339])
+            .filter_map(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc))
+            .max()
+    }
+}
+
+fn render_rss(feed: &Feed) -> String {
+    let items = feed
+        .posts
+        .iter()
+        .map(|post| {
+            let url = feed.post_url(post);
+            let pub_date = DateTime::parse_from_rfc3339(&post.published_at_rfc3339)
+                .ok()
+                .map(|date| date.to_rfc2822());
+
+            ItemBuilder::default()
+                .title(Some(post.title.clone()))
+                .link(Some(url.clone()))
+                .description(Some(post.description.clone()))
+                .content(Some(post.content.clone()))
+                .pub_date(pub_date)
+                .guid(Some(GuidBuilder::default().value(url).permalink(true).build()))
+                .categories(
+                    post.categories
+                        .iter()
+                        .map(|category| CategoryBuilder::default().name(category.name.clone()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    ChannelBuilder::default()
+        .title(feed.title.clone())
+        .link(feed.link.clone())
+        .description(feed.description.clone())
+        .last_build_date(feed.last_modified().map(|date| date.to_rfc2822()))
+        .items(items)
+        .build()
+        .to_string()
+}
+
+fn render_atom(feed: &Feed) -> String {
+    let parse = |date: &str| DateTime::parse_from_rfc3339(date).unwrap_or_default();
+
+    let entries = feed
+        .posts
+        .iter()
+        .map(|post| {
+            let url = feed.post_url(post);
+
+            EntryBuilder::default()
+                .title(post.title.clone())
+                .id(url.clone())
+                .links(vec![LinkBuilder::default().href(url).rel("alternate").build()])
+                .published(Some(parse(&post.published_at_rfc3339)))
+                .updated(parse(&post.updated_at_rfc3339))
+                .summary(Some(Text::plain(post.description.clone())))
+                .content(Some(
+                    ContentBuilder::default()
+                        .value(Some(post.content.clone()))
+                        .content_type(Some("html".to_string()))
+                        .build(),
+                ))
+                .categories(
+                    post.categories
+                        .iter()
+                        .map(|category| {
+                            AtomCategoryBuilder::default()
+                                .term(category.slug.clone())
+                                .label(Some(category.name.clone()))
+                                .build()
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    FeedBuilder::default()
+        .title(feed.title.clone())
+        .id(feed.self_link.clone())
+        .subtitle(Some(Text::plain(feed.description.clone())))
+        .updated(feed.last_modified().map(FixedDateTime::from).unwrap_or_default())
+        .authors(vec![PersonBuilder::default().name(FEED_AUTHOR).build()])
+        .links(vec![
+            LinkBuilder::default().href(feed.self_link.clone()).rel("self").build(),
+            LinkBuilder::default().href(feed.link.clone()).rel("alternate").build(),
+        ])
+        .entries(entries)
+        .build()
+        .to_string()
+}
+
+/// JSON Feed 1.1 document, see <https://www.jsonfeed.org/version/1.1/>.
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    home_page_url: &'a str,
+    feed_url: &'a str,
+    description: &'a str,
+    authors: [JsonFeedAuthor; 1],
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: String,
+    url: String,
+    title: &'a str,
+    content_html: &'a str,
+    summary: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    date_published: &'a str,
+    date_modified: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<&'a str>,
+}
+
+fn render_json_feed(feed: &Feed) -> String {
+    let items = feed
+        .posts
+        .iter()
+        .map(|post| JsonFeedItem {
+            id: feed.post_url(post),
+            url: feed.post_url(post),
+            title: &post.title,
+            content_html: &post.content,
+            summary: &post.description,
+            image: Some(&post.hero_image)
+                .filter(|image| !image.is_empty())
+                .map(|image| feed.absolute_url(image)),
+            date_published: &post.published_at_rfc3339,
+            date_modified: &post.updated_at_rfc3339,
+            tags: post.categories.iter().map(|category| category.name.as_str()).collect(),
+        })
+        .collect();
+
+    let json_feed = JsonFeed {
+        version: JSON_FEED_VERSION,
+        title: &feed.title,
+        home_page_url: &feed.link,
+        feed_url: &feed.self_link,
+        description: &feed.description,
+        authors: [JsonFeedAuthor { name: FEED_AUTHOR }],
+        items,
+    };
+
+    serde_json::to_string(&json_feed).unwrap_or_default()
+}
+
+/// Serves a rendered feed with `Last-Modified` and `ETag` validators,
+/// answering `304 Not Modified` when the client's copy is still current.
+fn feed_response(headers: &HeaderMap, feed: &Feed, content_type: &'static str, body: String) -> Response {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = format!("\"{:x}\"", hasher.finish());
+    let last_modified = feed.last_modified();
+
+    let etag_matches = if_none_match(headers, &etag);
+    let not_modified_since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .zip(last_modified)
+        .map(|(since, last_modified)| last_modified.timestamp() <= since.timestamp());
+
+    // `If-None-Match` takes precedence over `If-Modified-Since` when both are sent.
+    let status = match etag_matches.or(not_modified_since) {
+        Some(true) => StatusCode::NOT_MODIFIED,
+        _ => StatusCode::OK,
+    };
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    if let Ok(etag) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        let http_date = last_modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        if let Ok(http_date) = HeaderValue::from_str(&http_date) {
+            response_headers.insert(header::LAST_MODIFIED, http_date);
+        }
+    }
+
+    match status {
+        StatusCode::NOT_MODIFIED => (status, response_headers).into_response(),
+        _ => (status, response_headers, body).into_response(),
+    }
+}
+
+fn internal_error(error: RepositoryError) -> Response {
+    log::error!("failed to build feed: {error}");
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
@@ -1,9 +1,17 @@
 use app::*;
-use axum::Router;
+use axum::{middleware, routing::get, Router};
 use leptos::prelude::*;
 use leptos_axum::{generate_route_list, LeptosRoutes};
 use state::AppState;
 
+mod etag;
+mod feed;
+mod og;
+mod page_cache;
+mod search_index;
+mod sitemap;
+mod xml;
+
 #[tokio::main]
 async fn main() -> Result<()> {
     simple_logger::init_with_level(log::Level::Debug).expect("couldn't initialize logging");
@@ -24,11 +32,21 @@ async fn main() -> Result<()> {
 
     // build our application with a route
     let app = Router::new()
+        .route("/feed.xml", get(feed::rss))
+        .route("/atom.xml", get(feed::atom))
+        .route("/feed.json", get(feed::json))
+        .route("/blog/category/:slug/feed.xml", get(feed::category_rss))
+        .route("/sitemap.xml", get(sitemap::sitemap))
+        .route("/sitemaps/:file", get(sitemap::sitemap_page))
+        .route("/robots.txt", get(sitemap::robots))
+        .route("/og/:file", get(og::og_image))
+        .route("/search-index.bin", get(search_index::search_index))
         .leptos_routes(&app_state, routes, {
             let leptos_options = app_state.leptos_options.clone();
             move || shell(leptos_options.clone())
         })
         .fallback(leptos_axum::file_and_error_handler::<AppState, _>(shell))
+        .layer(middleware::from_fn_with_state(app_state.clone(), page_cache::page_cache_headers))
         .with_state(app_state);
 
     // run our app with hyper
@@ -0,0 +1,219 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path as FsPath, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use app::models::post::PostSummary;
+use app::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use resvg::{tiny_skia, usvg};
+
+use crate::xml::escape_xml;
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+
+const FONT_FAMILY: &str = "Geist";
+const FONT_FILES: [&str; 3] = ["Geist-Regular.woff2", "Geist-Medium.woff2", "Geist-Bold.woff2"];
+
+const TITLE_FONT_SIZE: usize = 64;
+const TITLE_LINE_HEIGHT: usize = 78;
+const TITLE_MAX_LINES: usize = 4;
+/// Rough number of bold title characters fitting in the card's text column.
+const TITLE_LINE_CHARS: usize = 30;
+
+static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+
+/// Serves `/og/{slug}.png`: a share card for the post with its title, date
+/// and categories. Cards are rendered once per version of those fields and
+/// then read back from the disk cache.
+pub async fn og_image(State(state): State<AppState>, Path(file): Path<String>) -> Response {
+    let Some(slug) = file.strip_suffix(".png") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let post = match state.db.get_post_summary_by_slug(slug).await {
+        Ok(Some(post)) => post,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            log::error!("failed to load post for og image: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let svg = render_svg(&post);
+    let mut hasher = DefaultHasher::new();
+    svg.hash(&mut hasher);
+    let cached = cache_dir().join(format!("{}-{:x}.png", post.slug, hasher.finish()));
+
+    let png = match tokio::fs::read(&cached).await {
+        Ok(png) => png,
+        Err(_) => {
+            let site_root = state.leptos_options.site_root.to_string();
+            let rendered = tokio::task::spawn_blocking(move || rasterize(&svg, FsPath::new(&site_root))).await;
+            let png = match rendered {
+                Ok(Some(png)) => png,
+                _ => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            };
+
+            match write_cache(&cached, &png).await {
+                Ok(()) => remove_stale_cards(&post.slug, &cached).await,
+                Err(e) => log::warn!("failed to cache og image {}: {e}", cached.display()),
+            }
+            png
+        }
+    };
+
+    ([(header::CONTENT_TYPE, "image/png")], png).into_response()
+}
+
+/// Directory rendered cards are cached in, `OG_CACHE_DIR` or `data/og`.
+fn cache_dir() -> PathBuf {
+    std::env::var("OG_CACHE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("data/og"))
+}
+
+async fn write_cache(path: &FsPath, png: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(path, png).await
+}
+
+/// Deletes the cards cached for earlier versions of the post, keeping
+/// `current`.
+async fn remove_stale_cards(slug: &str, current: &FsPath) {
+    let Some(dir) = current.parent() else {
+        return;
+    };
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("failed to list og image cache {}: {e}", dir.display());
+            return;
+        }
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path != current && is_card_of(&entry.file_name().to_string_lossy(), slug) {
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                log::warn!("failed to remove stale og image {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+/// Whether `file` is named `{slug}-{hash}.png`. Requiring a hex hash keeps
+/// the cards of `slug-more` from matching `slug`.
+fn is_card_of(file: &str, slug: &str) -> bool {
+    file.strip_prefix(slug)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|rest| rest.strip_suffix(".png"))
+        .is_some_and(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn render_svg(post: &PostSummary) -> String {
+    let categories = post
+        .categories
+        .iter()
+        .map(|category| category.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" · ");
+    let byline = if categories.is_empty() {
+        post.published_at.clone()
+    } else {
+        format!("{}  —  {categories}", post.published_at)
+    };
+
+    let lines = wrap(&post.title, TITLE_LINE_CHARS, TITLE_MAX_LINES);
+    // Keep the title block vertically centred between the byline and the branding.
+    let first_baseline = 330 - lines.len().saturating_sub(1) * TITLE_LINE_HEIGHT / 2;
+    let title = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            format!(
+                r#"<tspan x="80" y="{}">{}</tspan>"#,
+                first_baseline + index * TITLE_LINE_HEIGHT,
+                escape_xml(line)
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}">
+<rect width="{WIDTH}" height="{HEIGHT}" fill="#0f172a"/>
+<rect width="{WIDTH}" height="12" fill="#2dd4bf"/>
+<text x="80" y="120" font-family="{FONT_FAMILY}" font-weight="500" font-size="30" fill="#94a3b8">{byline}</text>
+<text font-family="{FONT_FAMILY}" font-weight="700" font-size="{TITLE_FONT_SIZE}" fill="#f8fafc">{title}</text>
+<text x="80" y="560" font-family="{FONT_FAMILY}" font-weight="700" font-size="34" fill="#2dd4bf">{site}</text>
+</svg>"##,
+        byline = escape_xml(&byline),
+        site = escape_xml(app::SITE_TITLE),
+    )
+}
+
+fn rasterize(svg: &str, site_root: &FsPath) -> Option<Vec<u8>> {
+    let options = usvg::Options {
+        font_family: FONT_FAMILY.to_string(),
+        fontdb: FONTS.get_or_init(|| Arc::new(load_fonts(site_root))).clone(),
+        ..Default::default()
+    };
+
+    let tree = usvg::Tree::from_str(svg, &options)
+        .inspect_err(|e| log::error!("failed to parse og image svg: {e}"))
+        .ok()?;
+    let mut pixmap = tiny_skia::Pixmap::new(WIDTH, HEIGHT)?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap
+        .encode_png()
+        .inspect_err(|e| log::error!("failed to encode og image: {e}"))
+        .ok()
+}
+
+/// Loads the Geist fonts served from `public/fonts`, which only ship as WOFF2.
+fn load_fonts(site_root: &FsPath) -> usvg::fontdb::Database {
+    let mut fonts = usvg::fontdb::Database::new();
+    for file in FONT_FILES {
+        let path = site_root.join("fonts/geist").join(file);
+        let font = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|woff2| wuff::decompress_woff2(&woff2).map_err(|e| format!("{e:?}")));
+        match font {
+            Ok(font) => fonts.load_font_data(font),
+            Err(e) => log::warn!("failed to load font {}: {e}", path.display()),
+        }
+    }
+    fonts
+}
+
+/// Greedily wraps `text` into at most `max_lines` lines of about
+/// `line_chars` characters, ending with an ellipsis when it does not fit.
+fn wrap(text: &str, line_chars: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= line_chars => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            last.push('…');
+        }
+    }
+    lines
+}
@@ -0,0 +1,82 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use app::state::AppState;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::etag::if_none_match;
+
+/// Public pages may be stored by browsers and proxies, but must be
+/// revalidated against their `ETag` before each reuse.
+const PUBLIC_CACHE_CONTROL: &str = "public, no-cache";
+/// Admin pages, post previews and responses setting cookies are meant for
+/// one user only and are never stored.
+const PRIVATE_CACHE_CONTROL: &str = "private, no-store";
+
+/// Adds `Cache-Control` to server-rendered pages, and an `ETag` to public
+/// ones. The `ETag` is derived from the URL and the read cache's version
+/// rather than from the page itself, so a client whose copy is current
+/// gets `304 Not Modified` before anything is rendered, and pages still
+/// stream. Responses that are not successful HTML pages or already carry
+/// `Cache-Control` pass through untouched.
+pub async fn page_cache_headers(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let is_private = is_private(request.uri());
+    let etag = (!is_private)
+        .then(|| state.cache.version())
+        .flatten()
+        .map(|version| page_etag(&version, request.uri()));
+
+    if let Some(etag) = etag.as_deref().filter(|etag| if_none_match(request.headers(), etag) == Some(true)) {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        insert_cache_headers(response.headers_mut(), PUBLIC_CACHE_CONTROL, Some(etag));
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+    if !is_html || response.status() != StatusCode::OK || response.headers().contains_key(header::CACHE_CONTROL) {
+        return response;
+    }
+
+    if is_private || response.headers().contains_key(header::SET_COOKIE) {
+        insert_cache_headers(response.headers_mut(), PRIVATE_CACHE_CONTROL, None);
+    } else {
+        insert_cache_headers(response.headers_mut(), PUBLIC_CACHE_CONTROL, etag.as_deref());
+    }
+    response
+}
+
+/// Admin pages and post previews, whose content depends on who asks.
+fn is_private(uri: &Uri) -> bool {
+    uri.path().starts_with("/admin")
+        || uri
+            .query()
+            .is_some_and(|query| query.split('&').any(|pair| pair.split('=').next() == Some("preview")))
+}
+
+fn page_etag(version: &str, uri: &Uri) -> String {
+    let mut hasher = DefaultHasher::new();
+    version.hash(&mut hasher);
+    uri.hash(&mut hasher);
+    format!("\"page-{:x}\"", hasher.finish())
+}
+
+fn insert_cache_headers(headers: &mut HeaderMap, cache_control: &'static str, etag: Option<&str>) {
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    if let Some(etag) = etag.and_then(|etag| HeaderValue::from_str(etag).ok()) {
+        headers.insert(header::ETAG, etag);
+    }
+}
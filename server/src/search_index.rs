@@ -0,0 +1,58 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use app::models::search_index::{EncodedSearchIndex, SearchIndex};
+use app::state::AppState;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::etag::if_none_match;
+
+/// Browsers may keep the index, but must check its `ETag` before reusing it.
+const CACHE_CONTROL: &str = "public, no-cache";
+
+/// Serves the prebuilt index the instant search palette queries in the
+/// browser, bincode-encoded. The index is built once and kept until a post
+/// is written or it expires.
+pub async fn search_index(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let index = state
+        .search_index
+        .get_or_try_insert_with(|| build_index(&state))
+        .await;
+    let Ok(index) = index else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/octet-stream"));
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+    if let Ok(etag) = HeaderValue::from_str(&index.etag) {
+        response_headers.insert(header::ETAG, etag);
+    }
+
+    if if_none_match(&headers, &index.etag) == Some(true) {
+        (StatusCode::NOT_MODIFIED, response_headers).into_response()
+    } else {
+        (response_headers, index.bytes).into_response()
+    }
+}
+
+async fn build_index(state: &AppState) -> Result<EncodedSearchIndex, ()> {
+    let sources = state
+        .db
+        .get_search_index_sources()
+        .await
+        .map_err(|e| log::error!("failed to load posts for the search index: {e}"))?;
+    let bytes = SearchIndex::build(sources)
+        .to_bytes()
+        .map_err(|e| log::error!("failed to encode the search index: {e}"))?;
+
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Ok(EncodedSearchIndex {
+        bytes: bytes.into(),
+        etag: format!("\"{:x}\"", hasher.finish()),
+    })
+}
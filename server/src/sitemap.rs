@@ -0,0 +1,127 @@
+use app::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::xml::escape_xml;
+
+/// Most URLs a single sitemap may list, per the sitemaps.org protocol.
+const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+struct SitemapUrl {
+    loc: String,
+    lastmod: Option<DateTime<Utc>>,
+}
+
+/// Serves the sitemap itself, or a sitemap index pointing at
+/// `/sitemaps/{n}.xml` once there are too many URLs for a single file.
+pub async fn sitemap(State(state): State<AppState>) -> Response {
+    let urls = match sitemap_urls(&state).await {
+        Ok(urls) => urls,
+        Err(e) => return internal_error(e),
+    };
+
+    if urls.len() <= MAX_URLS_PER_SITEMAP {
+        return xml_response(render_urlset(&urls));
+    }
+
+    let sitemaps = urls
+        .chunks(MAX_URLS_PER_SITEMAP)
+        .enumerate()
+        .map(|(index, chunk)| SitemapUrl {
+            loc: format!("{}/sitemaps/{}.xml", state.site_url, index + 1),
+            lastmod: chunk.iter().filter_map(|url| url.lastmod).max(),
+        })
+        .collect::<Vec<_>>();
+
+    xml_response(render_sitemapindex(&sitemaps))
+}
+
+/// Serves the 1-based `page` of the sitemap listed by the sitemap index.
+pub async fn sitemap_page(State(state): State<AppState>, Path(file): Path<String>) -> Response {
+    let Some(page) = file.strip_suffix(".xml").and_then(|page| page.parse::<usize>().ok()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let urls = match sitemap_urls(&state).await {
+        Ok(urls) => urls,
+        Err(e) => return internal_error(e),
+    };
+
+    let chunk = page.checked_sub(1).and_then(|index| urls.chunks(MAX_URLS_PER_SITEMAP).nth(index));
+    match chunk {
+        Some(chunk) if urls.len() > MAX_URLS_PER_SITEMAP => xml_response(render_urlset(chunk)),
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+pub async fn robots(State(state): State<AppState>) -> Response {
+    let mut robots = String::from("User-agent: *\n");
+    if state.robots_disallow.is_empty() {
+        robots.push_str("Disallow:\n");
+    }
+    for path in &state.robots_disallow {
+        robots.push_str(&format!("Disallow: {path}\n"));
+    }
+    robots.push_str(&format!("\nSitemap: {}/sitemap.xml\n", state.site_url));
+
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], robots).into_response()
+}
+
+async fn sitemap_urls(state: &AppState) -> Result<Vec<SitemapUrl>, sqlx::Error> {
+    let posts = state.db.get_post_sitemap_entries().await?;
+    let categories = state.db.get_category_sitemap_entries().await?;
+    let latest = posts.iter().filter_map(|post| post.lastmod).max();
+
+    let pages = ["/", "/blog"].into_iter().map(|path| SitemapUrl {
+        loc: format!("{}{path}", state.site_url),
+        lastmod: latest,
+    });
+    let categories = categories.into_iter().map(|category| SitemapUrl {
+        loc: format!("{}/blog/category/{}", state.site_url, category.slug),
+        lastmod: category.lastmod,
+    });
+    let posts = posts.into_iter().map(|post| SitemapUrl {
+        loc: format!("{}/blog/{}", state.site_url, post.slug),
+        lastmod: post.lastmod,
+    });
+
+    Ok(pages.chain(categories).chain(posts).collect())
+}
+
+fn render_urlset(urls: &[SitemapUrl]) -> String {
+    render("urlset", "url", urls)
+}
+
+fn render_sitemapindex(sitemaps: &[SitemapUrl]) -> String {
+    render("sitemapindex", "sitemap", sitemaps)
+}
+
+fn render(root: &str, element: &str, urls: &[SitemapUrl]) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<{root} xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n"
+    );
+    for url in urls {
+        xml.push_str(&format!("<{element}><loc>{}</loc>", escape_xml(&url.loc)));
+        if let Some(lastmod) = url.lastmod {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod.to_rfc3339_opts(SecondsFormat::Secs, true)));
+        }
+        xml.push_str(&format!("</{element}>\n"));
+    }
+    xml.push_str(&format!("</{root}>\n"));
+    xml
+}
+
+fn xml_response(xml: String) -> Response {
+    ([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], xml).into_response()
+}
+
+fn internal_error(error: sqlx::Error) -> Response {
+    log::error!("failed to build sitemap: {error}");
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}